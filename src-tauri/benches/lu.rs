@@ -0,0 +1,21 @@
+// Время LU-разложения большой матрицы; с --features rayon исключение выполняется параллельно.
+// Запуск: cargo bench --bench lu [--features rayon]
+use std::hint::black_box;
+use std::time::Instant;
+
+use matrix_calc_lib::Matrix;
+
+fn main() {
+    let n = 2000;
+    // Диагональное преобладание делает матрицу заведомо невырожденной
+    let a = Matrix::from_fn(n, n, |i, j| if i == j { n as f64 } else { ((i * 31 + j * 17) % 23) as f64 - 11.0 });
+
+    let start = Instant::now();
+    let (lower, upper, _) = black_box(a.lu_decomposition().unwrap());
+    println!("LU {n}x{n}: {:?}", start.elapsed());
+    black_box((lower, upper));
+
+    let start = Instant::now();
+    black_box(a.determinant_lu().unwrap());
+    println!("determinant_lu {n}x{n}: {:?}", start.elapsed());
+}
@@ -0,0 +1,23 @@
+// Сравнение обычного умножения и алгоритма Штрассена около порога STRASSEN_THRESHOLD.
+// Запуск: cargo bench --bench strassen
+use std::hint::black_box;
+use std::time::Instant;
+
+use matrix_calc_lib::Matrix;
+
+fn main() {
+    for n in [128, 256, 512, 1024] {
+        let a = Matrix::from_fn(n, n, |i, j| ((i * 7 + j * 3) % 11) as f64 - 5.0);
+        let b = Matrix::from_fn(n, n, |i, j| ((i * 5 + j * 13) % 17) as f64 / 4.0);
+
+        let start = Instant::now();
+        black_box(a.naive_multiply(&b));
+        let naive = start.elapsed();
+
+        let start = Instant::now();
+        black_box(a.strassen_multiply(&b));
+        let strassen = start.elapsed();
+
+        println!("{n}x{n}: обычное {naive:?}, Штрассен {strassen:?}");
+    }
+}
@@ -1,4 +1,7 @@
+use std::cell::OnceCell;
+use std::collections::HashMap;
 use std::fmt;
+use std::io::BufRead;
 use std::str::FromStr;
 
 #[tauri::command(rename_all = "snake_case")]
@@ -40,22 +43,114 @@ fn mul_matrix(matrix_string1: String, matrix_string2: String) -> Result<String,
     matrix3
 }
 
+#[tauri::command(rename_all = "snake_case")]
+fn evaluate_expression(expr: String, matrices: HashMap<String, String>) -> Result<String, String> {
+    let mut parsed = HashMap::new();
+    for (name, matrix_string) in matrices {
+        let matrix = Matrix::from_str(&matrix_string).map_err(|e| format!("{name}: {e}"))?;
+        parsed.insert(name, matrix);
+    }
+    let result = Matrix::evaluate(&expr, &parsed)?;
+
+    Ok(result.to_string())
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
-        .invoke_handler(tauri::generate_handler![summ_matrix, mul_matrix, div_matrix])
+        .invoke_handler(tauri::generate_handler![summ_matrix, mul_matrix, div_matrix, evaluate_expression])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
 
 #[derive(Debug, PartialEq, Clone)]
-struct Matrix {
+pub struct Matrix {
     rows: usize,
     cols: usize,
     data: Vec<Vec<f64>>, // Изменено на f64 для работы с дробями
 }
 
+// Порог, ниже которого элемент считается нулевым при приведении к ступенчатому виду
+const ZERO_TOLERANCE: f64 = 1e-10;
+
+// Размер, начиная с которого квадратные матрицы перемножаются алгоритмом Штрассена
+const STRASSEN_THRESHOLD: usize = 128;
+
+// Число строк под ведущим элементом, начиная с которого исключение в LU выполняется параллельно
+#[cfg(feature = "rayon")]
+const PARALLEL_THRESHOLD: usize = 256;
+
+// Наибольшее число строк матрицы без столбцов в двоичном формате: такие строки не занимают
+// места в буфере, поэтому проверка длины данных их не ограничивает
+const MAX_EMPTY_ROWS: usize = 1 << 20;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum SolutionType {
+    Unique,
+    Infinite,
+    None,
+}
+
+// Размер результата двумерной свёртки
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ConvMode {
+    Full,
+    Same,
+    Valid,
+}
+
+// Стратегия выбора главного элемента в LU-разложении
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum PivotStrategy {
+    None,
+    Partial,
+    Full,
+}
+
+// Результат LU-разложения P * A * Q = L * U; перестановка столбцов Q есть только при полном выборе
+#[derive(Debug, PartialEq, Clone)]
+pub struct LuDecomposition {
+    pub lower: Matrix,
+    pub upper: Matrix,
+    pub row_permutation: Vec<usize>,
+    pub col_permutation: Option<Vec<usize>>,
+}
+
+// Отношение минимального ведущего элемента к максимальному, ниже которого система считается почти вырожденной
+const NEAR_SINGULAR_PIVOT_RATIO: f64 = 1e-10;
+
+// Решение системы и предупреждение о численной ненадёжности, если оно есть
+#[derive(Debug, PartialEq, Clone)]
+pub struct SolveReport {
+    pub solutions: Vec<f64>,
+    pub warning: Option<String>,
+}
+
+// Результат итерационного метода: последнее приближение и норма невязки после каждой итерации.
+// При расхождении или исчерпании итераций история сохраняется, а converged равно false
+#[derive(Debug, PartialEq, Clone)]
+pub struct IterativeSolution {
+    pub solutions: Vec<f64>,
+    pub residuals: Vec<f64>,
+    pub converged: bool,
+}
+
+// Направление объединения матриц: Row - друг под другом, Col - рядом
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Axis {
+    Row,
+    Col,
+}
+
+// Обратная матрица по формуле adj(A) / det(A) вместе с её составляющими
+#[derive(Debug, PartialEq, Clone)]
+pub struct AdjugateInverse {
+    pub inverse: Matrix,
+    pub adjugate: Matrix,
+    pub determinant: f64,
+}
+
 impl Matrix {
     fn new(rows: usize, cols: usize) -> Self {
         Matrix {
@@ -65,6 +160,254 @@ impl Matrix {
         }
     }
 
+    pub fn toeplitz(first_col: &[f64], first_row: &[f64]) -> Result<Matrix, String> {
+        if first_col.is_empty() || first_row.is_empty() {
+            return Err("Матрица пуста".to_string());
+        }
+        if first_col[0] != first_row[0] {
+            return Err("Первые элементы столбца и строки не совпадают".to_string());
+        }
+
+        let mut result = Matrix::new(first_col.len(), first_row.len());
+        for i in 0..result.rows {
+            for j in 0..result.cols {
+                // Элемент зависит только от номера диагонали j - i
+                result.data[i][j] = if i >= j { first_col[i - j] } else { first_row[j - i] };
+            }
+        }
+        Ok(result)
+    }
+
+    pub fn from_fn(rows: usize, cols: usize, f: impl Fn(usize, usize) -> f64) -> Matrix {
+        Matrix {
+            rows,
+            cols,
+            data: (0..rows).map(|i| (0..cols).map(|j| f(i, j)).collect()).collect(),
+        }
+    }
+
+    pub fn hilbert(n: usize) -> Matrix {
+        Matrix::from_fn(n, n, |i, j| 1.0 / (i + j + 1) as f64)
+    }
+
+    pub fn identity(n: usize) -> Matrix {
+        let mut result = Matrix::new(n, n);
+        for i in 0..n {
+            result.data[i][i] = 1.0;
+        }
+        result
+    }
+
+    // Углы задаются в радианах
+    pub fn rotation_2d(theta: f64) -> Matrix {
+        let (sin, cos) = theta.sin_cos();
+        Matrix {
+            rows: 2,
+            cols: 2,
+            data: vec![vec![cos, -sin], vec![sin, cos]],
+        }
+    }
+
+    pub fn rotation_3d_x(theta: f64) -> Matrix {
+        let (sin, cos) = theta.sin_cos();
+        Matrix {
+            rows: 3,
+            cols: 3,
+            data: vec![vec![1.0, 0.0, 0.0], vec![0.0, cos, -sin], vec![0.0, sin, cos]],
+        }
+    }
+
+    pub fn rotation_3d_y(theta: f64) -> Matrix {
+        let (sin, cos) = theta.sin_cos();
+        Matrix {
+            rows: 3,
+            cols: 3,
+            data: vec![vec![cos, 0.0, sin], vec![0.0, 1.0, 0.0], vec![-sin, 0.0, cos]],
+        }
+    }
+
+    pub fn rotation_3d_z(theta: f64) -> Matrix {
+        let (sin, cos) = theta.sin_cos();
+        Matrix {
+            rows: 3,
+            cols: 3,
+            data: vec![vec![cos, -sin, 0.0], vec![sin, cos, 0.0], vec![0.0, 0.0, 1.0]],
+        }
+    }
+
+    // Сопровождающая матрица многочлена c[0]*x^n + c[1]*x^(n-1) + ... + c[n]
+    // (коэффициенты от старшего к младшему, многочлен приводится к унитарному виду)
+    pub fn companion(coeffs: &[f64]) -> Result<Matrix, String> {
+        if coeffs.len() < 2 {
+            return Err("Степень многочлена должна быть не меньше единицы".to_string());
+        }
+        if coeffs[0] == 0.0 {
+            return Err("Старший коэффициент многочлена равен нулю".to_string());
+        }
+
+        let n = coeffs.len() - 1;
+        let mut result = Matrix::new(n, n);
+        for j in 0..n {
+            result.data[0][j] = -coeffs[j + 1] / coeffs[0];
+        }
+        for i in 1..n {
+            result.data[i][i - 1] = 1.0;
+        }
+        Ok(result)
+    }
+
+    pub fn filled(rows: usize, cols: usize, value: f64) -> Matrix {
+        Matrix {
+            rows,
+            cols,
+            data: vec![vec![value; cols]; rows],
+        }
+    }
+
+    pub fn ones(rows: usize, cols: usize) -> Matrix {
+        Matrix::filled(rows, cols, 1.0)
+    }
+
+    pub fn from_triplets(rows: usize, cols: usize, triplets: &[(usize, usize, f64)]) -> Result<Matrix, String> {
+        let mut result = Matrix::new(rows, cols);
+        for &(i, j, value) in triplets {
+            if i >= rows || j >= cols {
+                return Err("Индекс выходит за пределы матрицы".to_string());
+            }
+            result.data[i][j] += value;
+        }
+        Ok(result)
+    }
+
+    // Магический квадрат n x n: сиамский метод для нечётных n, метод дополнений для n кратных 4,
+    // метод Стрейчи (LUX) для остальных чётных n
+    pub fn magic_square(n: usize) -> Result<Matrix, String> {
+        if n == 0 || n == 2 {
+            return Err("Магического квадрата такого порядка не существует".to_string());
+        }
+
+        if n % 2 == 1 {
+            let mut result = Matrix::new(n, n);
+            let (mut i, mut j) = (0, n / 2);
+            for value in 1..=n * n {
+                result.data[i][j] = value as f64;
+                let (next_i, next_j) = ((i + n - 1) % n, (j + 1) % n);
+                if result.data[next_i][next_j] != 0.0 {
+                    i = (i + 1) % n;
+                } else {
+                    (i, j) = (next_i, next_j);
+                }
+            }
+            return Ok(result);
+        }
+
+        // Чётное n, кратное 4
+        if n % 4 != 2 {
+            return Ok(Matrix::from_fn(n, n, |i, j| {
+                let value = (i * n + j + 1) as f64;
+                if i % 4 == j % 4 || (i % 4) + (j % 4) == 3 {
+                    (n * n + 1) as f64 - value
+                } else {
+                    value
+                }
+            }));
+        }
+
+        let p = n / 2;
+        let quarter = Matrix::magic_square(p)?;
+        let shift = (p * p) as f64;
+        let mut result = Matrix::from_fn(n, n, |i, j| {
+            let offset = match (i < p, j < p) {
+                (true, true) => 0.0,
+                (true, false) => 2.0 * shift,
+                (false, true) => 3.0 * shift,
+                (false, false) => shift,
+            };
+            quarter.data[i % p][j % p] + offset
+        });
+
+        let k = (n - 2) / 4;
+        let swap_columns: Vec<usize> = (0..k).chain(n - k + 1..n).collect();
+        for i in 0..p {
+            for &j in &swap_columns {
+                let upper = result.data[i][j];
+                result.data[i][j] = result.data[i + p][j];
+                result.data[i + p][j] = upper;
+            }
+        }
+        for j in [0, k] {
+            let upper = result.data[k][j];
+            result.data[k][j] = result.data[k + p][j];
+            result.data[k + p][j] = upper;
+        }
+        Ok(result)
+    }
+
+    // rows * cols равноотстоящих значений от start до end, заполняемых построчно
+    pub fn linspace_grid(start: f64, end: f64, rows: usize, cols: usize) -> Matrix {
+        let count = rows * cols;
+        let step = if count > 1 { (end - start) / (count - 1) as f64 } else { 0.0 };
+        Matrix::from_fn(rows, cols, |i, j| {
+            let idx = i * cols + j;
+            if idx + 1 == count && count > 1 {
+                end
+            } else {
+                start + step * idx as f64
+            }
+        })
+    }
+
+    // Элементарные матрицы: умножение слева на них выполняет соответствующее преобразование строк
+    pub fn elementary_swap(n: usize, i: usize, j: usize) -> Result<Matrix, String> {
+        if i >= n || j >= n {
+            return Err("Индекс выходит за пределы матрицы".to_string());
+        }
+
+        let mut result = Matrix::identity(n);
+        result.data.swap(i, j);
+        Ok(result)
+    }
+
+    pub fn elementary_scale(n: usize, i: usize, factor: f64) -> Result<Matrix, String> {
+        if i >= n {
+            return Err("Индекс выходит за пределы матрицы".to_string());
+        }
+
+        let mut result = Matrix::identity(n);
+        result.data[i][i] = factor;
+        Ok(result)
+    }
+
+    // Прибавляет к строке target строку source, умноженную на factor
+    pub fn elementary_add(n: usize, target: usize, source: usize, factor: f64) -> Result<Matrix, String> {
+        if target >= n || source >= n {
+            return Err("Индекс выходит за пределы матрицы".to_string());
+        }
+        if target == source {
+            return Err("Строки должны различаться".to_string());
+        }
+
+        let mut result = Matrix::identity(n);
+        result.data[target][source] = factor;
+        Ok(result)
+    }
+
+    // Матрица Ганкеля из скользящих окон сигнала: строка i - окно, начинающееся с i-го отсчёта,
+    // длина окна signal.len() - rows + 1
+    pub fn hankel(signal: &[f64], rows: usize) -> Result<Matrix, String> {
+        if rows == 0 || rows > signal.len() {
+            return Err("Сигнал слишком короткий для заданного количества строк".to_string());
+        }
+
+        let cols = signal.len() - rows + 1;
+        Ok(Matrix::from_fn(rows, cols, |i, j| signal[i + j]))
+    }
+
+    // Шахматная матрица знаков (-1)^(i + j), как в разложении по алгебраическим дополнениям
+    pub fn checkerboard(rows: usize, cols: usize) -> Matrix {
+        Matrix::from_fn(rows, cols, |i, j| if (i + j) % 2 == 1 { -1.0 } else { 1.0 })
+    }
+
     fn determinant(&self) -> Result<f64, String> {
         if self.rows != self.cols {
             return Err("Определитель может быть вычислен только для квадратной матрицы".to_string());
@@ -78,6 +421,11 @@ impl Matrix {
             return Ok(self.data[0][0] * self.data[1][1] - self.data[0][1] * self.data[1][0]);
         }
 
+        // Определитель треугольной матрицы - произведение диагональных элементов
+        if self.is_triangular(0.0) {
+            return Ok((0..self.rows).map(|i| self.data[i][i]).product());
+        }
+
         let mut det = 0.0;
         for j in 0..self.cols {
             let mut submatrix = Matrix::new(self.rows - 1, self.cols - 1);
@@ -107,6 +455,11 @@ impl Matrix {
     }
 
     fn inverse(&self) -> Result<Matrix, String> {
+        // Нулевая строка или столбец гарантируют вырожденность, определитель можно не считать
+        if self.rows == self.cols && (self.has_zero_row() || self.has_zero_col()) {
+            return Err("У данной матрицы нет обратной матрицы(есть нулевая строка или столбец)".to_string());
+        }
+
         let det = self.determinant()?;
         if det == 0.0 {
             return Err("У данной матрицы нет обратной матрицы(определитель равен нулю)".to_string());
@@ -120,26 +473,45 @@ impl Matrix {
             });
         }
 
+        // Явные формулы для матриц 2x2 и 3x3
+        if self.rows == 2 {
+            let d = &self.data;
+            return Ok(Matrix {
+                rows: 2,
+                cols: 2,
+                data: vec![vec![d[1][1], -d[0][1]], vec![-d[1][0], d[0][0]]],
+            } * (1.0 / det));
+        }
+
+        if self.rows == 3 {
+            let d = &self.data;
+            return Ok(Matrix {
+                rows: 3,
+                cols: 3,
+                data: vec![
+                    vec![
+                        d[1][1] * d[2][2] - d[1][2] * d[2][1],
+                        d[0][2] * d[2][1] - d[0][1] * d[2][2],
+                        d[0][1] * d[1][2] - d[0][2] * d[1][1],
+                    ],
+                    vec![
+                        d[1][2] * d[2][0] - d[1][0] * d[2][2],
+                        d[0][0] * d[2][2] - d[0][2] * d[2][0],
+                        d[0][2] * d[1][0] - d[0][0] * d[1][2],
+                    ],
+                    vec![
+                        d[1][0] * d[2][1] - d[1][1] * d[2][0],
+                        d[0][1] * d[2][0] - d[0][0] * d[2][1],
+                        d[0][0] * d[1][1] - d[0][1] * d[1][0],
+                    ],
+                ],
+            } * (1.0 / det));
+        }
+
         let mut adjugate = Matrix::new(self.rows, self.cols);
         for i in 0..self.rows {
             for j in 0..self.cols {
-                let mut submatrix = Matrix::new(self.rows - 1, self.cols - 1);
-                let mut row_idx = 0;
-                for k in 0..self.rows {
-                    if k == i {
-                        continue;
-                    }
-                    let mut col_idx = 0;
-                    for l in 0..self.cols {
-                        if l == j {
-                            continue;
-                        }
-                        submatrix.data[row_idx][col_idx] = self.data[k][l];
-                        col_idx += 1;
-                    }
-                    row_idx += 1;
-                }
-                adjugate.data[i][j] = submatrix.determinant()? * if (i + j) % 2 == 0 { 1.0 } else { -1.0 };
+                adjugate.data[i][j] = self.minor_matrix(i, j).determinant()? * if (i + j) % 2 == 0 { 1.0 } else { -1.0 };
             }
         }
 
@@ -148,13 +520,42 @@ impl Matrix {
     }
 
     fn gaussian_elimination(&self) -> Result<Vec<f64>, String> {
+        let (solutions, _, _) = self.gaussian_elimination_with_permutation()?;
+        Ok(solutions)
+    }
+
+    // Возвращает решение, расширенную матрицу в ступенчатом виде и перестановку строк:
+    // permutation[i] - номер исходной строки, оказавшейся на i-м месте
+    pub fn gaussian_elimination_with_permutation(&self) -> Result<(Vec<f64>, Matrix, Vec<usize>), String> {
         if self.rows + 1 != self.cols {
             return Err("У матрицы неправильная размерность для метода Гаусса".to_string());
         }
 
+        let (augmented_matrix, permutation) = self.forward_elimination(|_, _| {});
+        let solutions = Matrix::back_substitution(&augmented_matrix);
+        Ok((solutions, augmented_matrix, permutation))
+    }
+
+    // Обратный ход (нахождение решения) по расширенной матрице в ступенчатом виде
+    fn back_substitution(augmented_matrix: &Matrix) -> Vec<f64> {
+        let n = augmented_matrix.rows;
+        let mut solutions = vec![0.0; n];
+        for i in (0..n).rev() {
+            solutions[i] = augmented_matrix.data[i][n];
+            for j in i + 1..n {
+                solutions[i] -= augmented_matrix.data[i][j] * solutions[j];
+            }
+            solutions[i] /= augmented_matrix.data[i][i];
+        }
+        solutions
+    }
+
+    // Прямой ход метода Гаусса над расширенной матрицей (приведение к треугольному виду);
+    // on_step получает описание каждой операции над строками и матрицу после неё
+    fn forward_elimination(&self, mut on_step: impl FnMut(String, &Matrix)) -> (Matrix, Vec<usize>) {
         let mut augmented_matrix = self.clone(); // Работаем с копией, чтобы не изменять исходную матрицу
+        let mut permutation: Vec<usize> = (0..self.rows).collect();
 
-        // Прямой ход (приведение к треугольному виду)
         for i in 0..self.rows {
             // Находим максимальный элемент в столбце i (начиная с i-й строки)
             let mut max_row = i;
@@ -167,6 +568,8 @@ impl Matrix {
             // Меняем строки местами, если нужно
             if max_row != i {
                 augmented_matrix.data.swap(i, max_row);
+                permutation.swap(i, max_row);
+                on_step(format!("R{} <-> R{}", i + 1, max_row + 1), &augmented_matrix);
             }
 
             // Обнуляем элементы ниже i-го элемента в столбце i
@@ -175,20 +578,13 @@ impl Matrix {
                 for j in i..self.cols {
                     augmented_matrix.data[k][j] -= factor * augmented_matrix.data[i][j];
                 }
+                if factor != 0.0 {
+                    on_step(format!("R{} = R{} - ({}) * R{}", k + 1, k + 1, factor, i + 1), &augmented_matrix);
+                }
             }
         }
 
-        // Обратный ход (нахождение решения)
-        let mut solutions = vec![0.0; self.rows];
-        for i in (0..self.rows).rev() {
-            solutions[i] = augmented_matrix.data[i][self.cols - 1];
-            for j in i + 1..self.rows {
-                solutions[i] -= augmented_matrix.data[i][j] * solutions[j];
-            }
-            solutions[i] /= augmented_matrix.data[i][i];
-        }
-
-        Ok(solutions)
+        (augmented_matrix, permutation)
     }
 
     fn cramer_rule(&self) -> Result<Vec<f64>, String> {
@@ -207,8 +603,11 @@ impl Matrix {
 
         let det_a = core_matrix.determinant()?; // Определитель основной матрицы
 
-        if det_a == 0.0 {
-            return Err("Система не имеет решений(определитель равен нулю)".to_string());
+        // Величина определителя зависит от масштаба, поэтому вырожденность проверяется, как в solve,
+        // по отношению ведущих элементов LU-разложения
+        let (_, upper, _) = core_matrix.lu_decomposition()?;
+        if det_a == 0.0 || Matrix::pivot_ratio(&upper) < NEAR_SINGULAR_PIVOT_RATIO {
+            return Err("Система не имеет единственного решения(определитель равен нулю)".to_string());
         }
 
         let mut solutions = vec![0.0; n];
@@ -222,127 +621,368 @@ impl Matrix {
 
         Ok(solutions)
     }
-}
 
-impl FromStr for Matrix {
-    type Err = String;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let rows: Vec<&str> = s.trim().split('\n').collect();
-        let rows_count = rows.len();
-        if rows_count == 0 {
-            return Err("Матрица пуста".to_string());
-        }
-
-        let cols_count = rows[0].trim().split_whitespace().count();
-        if cols_count == 0 {
-            return Err("Матрица пуста".to_string());
+    pub fn pad(&self, top: usize, bottom: usize, left: usize, right: usize, value: f64) -> Matrix {
+        let rows = top + self.rows + bottom;
+        let cols = left + self.cols + right;
+        let mut padded = Matrix {
+            rows,
+            cols,
+            data: vec![vec![value; cols]; rows],
+        };
+        for i in 0..self.rows {
+            padded.data[top + i][left..left + self.cols].copy_from_slice(&self.data[i]);
         }
+        padded
+    }
 
-        let mut data = Vec::with_capacity(rows_count);
-        for row_str in rows {
-            let row: Vec<f64> = row_str // Изменено на f64
-                .trim()
-                .split_whitespace()
-                .map(|s| s.parse().map_err(|_| "Неправильное значение в матрице".to_string()))
-                .collect::<Result<_, _>>()?;
-            if row.len() != cols_count {
-                return Err("Неправильное количество символов в строке матрицы".to_string());
+    // Матрица без строки row и столбца col
+    fn minor_matrix(&self, row: usize, col: usize) -> Matrix {
+        let mut submatrix = Matrix::new(self.rows - 1, self.cols - 1);
+        let mut row_idx = 0;
+        for k in 0..self.rows {
+            if k == row {
+                continue;
             }
-            data.push(row);
+            let mut col_idx = 0;
+            for l in 0..self.cols {
+                if l == col {
+                    continue;
+                }
+                submatrix.data[row_idx][col_idx] = self.data[k][l];
+                col_idx += 1;
+            }
+            row_idx += 1;
         }
-
-        Ok(Matrix {
-            rows: rows_count,
-            cols: cols_count,
-            data,
-        })
+        submatrix
     }
-}
 
-impl fmt::Display for Matrix {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let max_widths: Vec<usize> = (0..self.cols)
-            .map(|j| {
-                self.data
-                    .iter()
-                    .map(|row| row[j].to_string().len())
-                    .max()
-                    .unwrap_or(0)
-            })
-            .collect();
+    pub fn row_cofactors(&self, row: usize) -> Result<Vec<f64>, String> {
+        if self.rows != self.cols {
+            return Err("Алгебраические дополнения определены только для квадратной матрицы".to_string());
+        }
+        if row >= self.rows {
+            return Err("Номер строки выходит за пределы матрицы".to_string());
+        }
 
-        for row in &self.data {
-            for (j, &val) in row.iter().enumerate() {
-                write!(f, " {:^width$} ", val, width = max_widths[j])?;
-            }
-            writeln!(f)?;
+        if self.rows == 1 {
+            return Ok(vec![1.0]);
         }
 
-        Ok(())
+        let mut cofactors = Vec::with_capacity(self.cols);
+        for j in 0..self.cols {
+            let sign = if (row + j) % 2 == 1 { -1.0 } else { 1.0 };
+            cofactors.push(self.minor_matrix(row, j).determinant()? * sign);
+        }
+        Ok(cofactors)
     }
-}
 
-impl std::ops::Add for Matrix {
-    type Output = Result<Matrix, String>;
-
-    fn add(self, other: Matrix) -> Self::Output {
-        if self.rows != other.rows || self.cols != other.cols {
-            return Err("Матрицы разной размерности".to_string());
+    pub fn symmetrize(&self) -> Result<Matrix, String> {
+        if self.rows != self.cols {
+            return Err("Симметричная часть определена только для квадратной матрицы".to_string());
         }
+        Ok((self.clone() + self.transpose())? * 0.5)
+    }
 
-        let mut result = Matrix::new(self.rows, self.cols);
-        for i in 0..self.rows {
-            for j in 0..self.cols {
-                result.data[i][j] = self.data[i][j] + other.data[i][j];
-            }
+    pub fn skew_symmetrize(&self) -> Result<Matrix, String> {
+        if self.rows != self.cols {
+            return Err("Кососимметричная часть определена только для квадратной матрицы".to_string());
         }
+        Ok((self.clone() - self.transpose())? * 0.5)
+    }
 
-        Ok(result)
+    pub fn to_vec(&self) -> Vec<f64> {
+        self.data.iter().flatten().copied().collect()
     }
-}
 
-impl std::ops::Sub for Matrix {
-    type Output = Result<Matrix, String>;
+    pub fn to_nested_vec(&self) -> Vec<Vec<f64>> {
+        self.data.clone()
+    }
 
-    fn sub(self, other: Matrix) -> Self::Output {
-        if self.rows != other.rows || self.cols != other.cols {
-            return Err("Матрицы разной размерности".to_string());
-        }
+    pub fn shape(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
 
-        let mut result = Matrix::new(self.rows, self.cols);
-        for i in 0..self.rows {
-            for j in 0..self.cols {
-                result.data[i][j] = self.data[i][j] - other.data[i][j];
-            }
-        }
+    pub fn len(&self) -> usize {
+        self.rows * self.cols
+    }
 
-        Ok(result)
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
     }
-}
 
-impl std::ops::Mul<f64> for Matrix {
-    type Output = Matrix;
+    // LU-разложение с выбором главного элемента по столбцу: P * A = L * U,
+    // permutation[i] - номер исходной строки, оказавшейся на i-м месте
+    pub fn lu_decomposition(&self) -> Result<(Matrix, Matrix, Vec<usize>), String> {
+        let lu = self.lu_decomposition_pivoted(PivotStrategy::Partial)?;
+        Ok((lu.lower, lu.upper, lu.row_permutation))
+    }
 
-    fn mul(self, scalar: f64) -> Self::Output {
-        let mut result = Matrix::new(self.rows, self.cols);
-        for i in 0..self.rows {
-            for j in 0..self.cols {
-                result.data[i][j] = self.data[i][j] * scalar;
-            }
+    // LU-разложение с заданной стратегией выбора главного элемента: P * A * Q = L * U
+    pub fn lu_decomposition_pivoted(&self, strategy: PivotStrategy) -> Result<LuDecomposition, String> {
+        if self.rows != self.cols {
+            return Err("LU-разложение может быть вычислено только для квадратной матрицы".to_string());
+        }
+
+        let n = self.rows;
+        let mut lower = Matrix::new(n, n);
+        let mut upper = self.clone();
+        let mut row_permutation: Vec<usize> = (0..n).collect();
+        let mut col_permutation: Vec<usize> = (0..n).collect();
+
+        for i in 0..n {
+            let (mut max_row, mut max_col) = (i, i);
+            match strategy {
+                PivotStrategy::None => {}
+                PivotStrategy::Partial => {
+                    for k in i + 1..n {
+                        if upper.data[k][i].abs() > upper.data[max_row][i].abs() {
+                            max_row = k;
+                        }
+                    }
+                }
+                PivotStrategy::Full => {
+                    for k in i..n {
+                        for l in i..n {
+                            if upper.data[k][l].abs() > upper.data[max_row][max_col].abs() {
+                                max_row = k;
+                                max_col = l;
+                            }
+                        }
+                    }
+                }
+            }
+
+            if max_row != i {
+                upper.data.swap(i, max_row);
+                lower.data.swap(i, max_row);
+                row_permutation.swap(i, max_row);
+            }
+            if max_col != i {
+                for row in upper.data.iter_mut() {
+                    row.swap(i, max_col);
+                }
+                col_permutation.swap(i, max_col);
+            }
+
+            if upper.data[i][i] == 0.0 {
+                // Столбец уже нулевой, исключать нечего
+                if (i + 1..n).all(|k| upper.data[k][i] == 0.0) {
+                    continue;
+                }
+                return Err("Нулевой ведущий элемент, разложение без перестановок невозможно".to_string());
+            }
+
+            Matrix::eliminate_below(&mut lower, &mut upper, i);
+        }
+
+        for i in 0..n {
+            lower.data[i][i] = 1.0;
+        }
+
+        Ok(LuDecomposition {
+            lower,
+            upper,
+            row_permutation,
+            col_permutation: if strategy == PivotStrategy::Full { Some(col_permutation) } else { None },
+        })
+    }
+
+    // Исключение элементов столбца i ниже ведущего; строки независимы, поэтому
+    // при включённой feature "rayon" большие матрицы обрабатываются параллельно
+    fn eliminate_below(lower: &mut Matrix, upper: &mut Matrix, i: usize) {
+        #[cfg(feature = "rayon")]
+        if upper.rows > i + PARALLEL_THRESHOLD {
+            use rayon::prelude::*;
+            let (head, tail) = upper.data.split_at_mut(i + 1);
+            let pivot_row = &head[i];
+            tail.par_iter_mut()
+                .zip(lower.data[i + 1..].par_iter_mut())
+                .for_each(|(row, lower_row)| Matrix::eliminate_row(row, lower_row, pivot_row, i));
+            return;
+        }
+
+        Matrix::eliminate_below_serial(lower, upper, i);
+    }
+
+    fn eliminate_below_serial(lower: &mut Matrix, upper: &mut Matrix, i: usize) {
+        let (head, tail) = upper.data.split_at_mut(i + 1);
+        let pivot_row = &head[i];
+        for (row, lower_row) in tail.iter_mut().zip(lower.data[i + 1..].iter_mut()) {
+            Matrix::eliminate_row(row, lower_row, pivot_row, i);
+        }
+    }
+
+    fn eliminate_row(row: &mut [f64], lower_row: &mut [f64], pivot_row: &[f64], i: usize) {
+        let factor = row[i] / pivot_row[i];
+        lower_row[i] = factor;
+        for (value, &pivot_value) in row[i..].iter_mut().zip(&pivot_row[i..]) {
+            *value -= factor * pivot_value;
+        }
+    }
+
+    pub fn determinant_lu(&self) -> Result<f64, String> {
+        let (_, upper, permutation) = self.lu_decomposition()?;
+        let pivots: f64 = (0..upper.rows).map(|i| upper.data[i][i]).product();
+        Ok(Matrix::permutation_sign(&permutation) * pivots)
+    }
+
+    // Знак перестановки: +1 для чётной, -1 для нечётной
+    fn permutation_sign(permutation: &[usize]) -> f64 {
+        let mut visited = vec![false; permutation.len()];
+        let mut sign = 1.0;
+        for start in 0..permutation.len() {
+            if visited[start] {
+                continue;
+            }
+            let mut cycle_len = 0;
+            let mut i = start;
+            while !visited[i] {
+                visited[i] = true;
+                i = permutation[i];
+                cycle_len += 1;
+            }
+            if cycle_len % 2 == 0 {
+                sign = -sign;
+            }
+        }
+        sign
+    }
+
+    // Знак определителя и натуральный логарифм его модуля
+    pub fn slogdet(&self) -> Result<(f64, f64), String> {
+        let (_, upper, permutation) = self.lu_decomposition()?;
+
+        let mut sign = Matrix::permutation_sign(&permutation);
+        let mut log_det = 0.0;
+        for i in 0..upper.rows {
+            let pivot = upper.data[i][i];
+            if pivot == 0.0 {
+                return Err("Матрица вырождена (определитель равен нулю)".to_string());
+            }
+            if pivot < 0.0 {
+                sign = -sign;
+            }
+            log_det += pivot.abs().ln();
+        }
+
+        Ok((sign, log_det))
+    }
+
+    pub fn has_zero_row(&self) -> bool {
+        self.data.iter().any(|row| row.iter().all(|&x| x == 0.0))
+    }
+
+    pub fn has_zero_col(&self) -> bool {
+        (0..self.cols).any(|j| self.data.iter().all(|row| row[j] == 0.0))
+    }
+
+    pub fn format(&self, col_sep: &str, precision: usize) -> String {
+        let mut result = String::new();
+        for row in &self.data {
+            let cells: Vec<String> = row.iter().map(|val| format!("{:.*}", precision, val)).collect();
+            result.push_str(&cells.join(col_sep));
+            result.push('\n');
         }
         result
     }
-}
 
-impl std::ops::Mul for Matrix {
-    type Output = Result<Matrix, String>;
+    pub fn solve_as_fractions(&self) -> Result<Vec<String>, String> {
+        let solutions = self.gaussian_elimination()?;
+        Ok(solutions.into_iter().map(to_fraction_string).collect())
+    }
 
-    fn mul(self, other: Matrix) -> Self::Output {
-        if self.cols != other.rows {
-            return Err("У матриц не подходят размерности".to_string());
+    pub fn rank(&self) -> usize {
+        self.row_echelon().1.len()
+    }
+
+    // Ступенчатый вид и номера столбцов с ведущими элементами
+    pub fn row_echelon(&self) -> (Matrix, Vec<usize>) {
+        self.row_echelon_through(self.cols)
+    }
+
+    // Наибольший модуль элемента, но не меньше 1: масштаб для порогов вида ZERO_TOLERANCE * scale
+    fn element_scale(&self) -> f64 {
+        self.data.iter().flatten().fold(1.0_f64, |acc, x| acc.max(x.abs()))
+    }
+
+    // Прямой ход только по первым last_col столбцам
+    fn row_echelon_through(&self, last_col: usize) -> (Matrix, Vec<usize>) {
+        let scale = self.element_scale();
+        let tolerance = ZERO_TOLERANCE * scale;
+        self.row_echelon_with(last_col, |candidate| candidate > tolerance)
+    }
+
+    // Прямой ход с выбором главного элемента по столбцу; is_pivot решает по модулю
+    // наибольшего элемента столбца, брать ли его ведущим или пропустить столбец
+    fn row_echelon_with(&self, last_col: usize, mut is_pivot: impl FnMut(f64) -> bool) -> (Matrix, Vec<usize>) {
+        let mut reduced = self.clone();
+        let mut pivots = Vec::new();
+        let mut rank = 0;
+        for col in 0..last_col {
+            if rank == self.rows {
+                break;
+            }
+
+            let mut max_row = rank;
+            for k in rank + 1..self.rows {
+                if reduced.data[k][col].abs() > reduced.data[max_row][col].abs() {
+                    max_row = k;
+                }
+            }
+            if !is_pivot(reduced.data[max_row][col].abs()) {
+                continue;
+            }
+            reduced.data.swap(rank, max_row);
+
+            for k in rank + 1..self.rows {
+                let factor = reduced.data[k][col] / reduced.data[rank][col];
+                for j in col..self.cols {
+                    reduced.data[k][j] -= factor * reduced.data[rank][j];
+                }
+            }
+            pivots.push(col);
+            rank += 1;
+        }
+        (reduced, pivots)
+    }
+
+    pub fn pivot_columns(&self) -> Vec<usize> {
+        self.row_echelon().1
+    }
+
+    // Частично приведённая матрица после исключения по первым pivot столбцам:
+    // pivot = 0 - исходная матрица, pivot = cols - полный ступенчатый вид
+    pub fn eliminate_up_to(&self, pivot: usize) -> Result<Matrix, String> {
+        if pivot > self.cols {
+            return Err("Номер столбца выходит за пределы матрицы".to_string());
+        }
+        Ok(self.row_echelon_through(pivot).0)
+    }
+
+    // Классификация системы по теореме Кронекера-Капелли
+    pub fn solution_type(&self) -> Result<SolutionType, String> {
+        if self.cols < 2 {
+            return Err("У матрицы неправильная размерность для системы уравнений".to_string());
+        }
+
+        let unknowns = self.cols - 1;
+        let mut core_matrix = Matrix::new(self.rows, unknowns);
+        for i in 0..self.rows {
+            core_matrix.data[i].copy_from_slice(&self.data[i][..unknowns]);
+        }
+
+        let core_rank = core_matrix.rank();
+        if core_rank < self.rank() {
+            Ok(SolutionType::None)
+        } else if core_rank == unknowns {
+            Ok(SolutionType::Unique)
+        } else {
+            Ok(SolutionType::Infinite)
         }
+    }
 
+    pub fn naive_multiply(&self, other: &Matrix) -> Matrix {
         let mut result = Matrix::new(self.rows, other.cols);
         for i in 0..self.rows {
             for j in 0..other.cols {
@@ -351,84 +991,3442 @@ impl std::ops::Mul for Matrix {
                 }
             }
         }
+        result
+    }
+
+    // Поэлементно self + sign * other, размеры не проверяются
+    fn combine(&self, other: &Matrix, sign: f64) -> Matrix {
+        let mut result = self.clone();
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                result.data[i][j] += sign * other.data[i][j];
+            }
+        }
+        result
+    }
+
+    // Умножение квадратных матриц одного размера алгоритмом Штрассена
+    pub fn strassen_multiply(&self, other: &Matrix) -> Matrix {
+        let n = self.rows;
+        if n <= STRASSEN_THRESHOLD {
+            return self.naive_multiply(other);
+        }
+
+        // Нечётный размер дополняем нулевой строкой и столбцом
+        if n % 2 == 1 {
+            let a = self.pad(0, 1, 0, 1, 0.0);
+            let b = other.pad(0, 1, 0, 1, 0.0);
+            return a.strassen_multiply(&b).submatrix(0, 0, n, n);
+        }
+
+        let half = n / 2;
+        let (a11, a12) = (self.submatrix(0, 0, half, half), self.submatrix(0, half, half, half));
+        let (a21, a22) = (self.submatrix(half, 0, half, half), self.submatrix(half, half, half, half));
+        let (b11, b12) = (other.submatrix(0, 0, half, half), other.submatrix(0, half, half, half));
+        let (b21, b22) = (other.submatrix(half, 0, half, half), other.submatrix(half, half, half, half));
+
+        let m1 = a11.combine(&a22, 1.0).strassen_multiply(&b11.combine(&b22, 1.0));
+        let m2 = a21.combine(&a22, 1.0).strassen_multiply(&b11);
+        let m3 = a11.strassen_multiply(&b12.combine(&b22, -1.0));
+        let m4 = a22.strassen_multiply(&b21.combine(&b11, -1.0));
+        let m5 = a11.combine(&a12, 1.0).strassen_multiply(&b22);
+        let m6 = a21.combine(&a11, -1.0).strassen_multiply(&b11.combine(&b12, 1.0));
+        let m7 = a12.combine(&a22, -1.0).strassen_multiply(&b21.combine(&b22, 1.0));
+
+        let c11 = m1.combine(&m4, 1.0).combine(&m5, -1.0).combine(&m7, 1.0);
+        let c12 = m3.combine(&m5, 1.0);
+        let c21 = m2.combine(&m4, 1.0);
+        let c22 = m1.combine(&m2, -1.0).combine(&m3, 1.0).combine(&m6, 1.0);
 
+        let mut result = Matrix::new(n, n);
+        for i in 0..half {
+            result.data[i][..half].copy_from_slice(&c11.data[i]);
+            result.data[i][half..].copy_from_slice(&c12.data[i]);
+            result.data[half + i][..half].copy_from_slice(&c21.data[i]);
+            result.data[half + i][half..].copy_from_slice(&c22.data[i]);
+        }
+        result
+    }
+
+    pub fn trace(&self) -> Result<f64, String> {
+        if self.rows != self.cols {
+            return Err("След может быть вычислен только для квадратной матрицы".to_string());
+        }
+        Ok((0..self.rows).map(|i| self.data[i][i]).sum())
+    }
+
+    // Возведение в степень быстрым умножением, A^0 - единичная матрица
+    pub fn pow(&self, exponent: u32) -> Result<Matrix, String> {
+        if self.rows != self.cols {
+            return Err("В степень можно возводить только квадратную матрицу".to_string());
+        }
+
+        let mut result = Matrix::identity(self.rows);
+        let mut base = self.clone();
+        let mut exponent = exponent;
+        while exponent > 0 {
+            if exponent % 2 == 1 {
+                result = (result * base.clone())?;
+            }
+            base = (base.clone() * base)?;
+            exponent /= 2;
+        }
         Ok(result)
     }
-}
 
-// fn main() {
-//     let matrix1_str = "1 2 \n4 5 ";
-//     let matrix2_str = "7 8\n   11 12";
+    // Следы степеней [tr(A), tr(A^2), ..., tr(A^k)]
+    pub fn power_traces(&self, k: usize) -> Result<Vec<f64>, String> {
+        if self.rows != self.cols {
+            return Err("След может быть вычислен только для квадратной матрицы".to_string());
+        }
 
-//     let matrix1 = Matrix::from_str(matrix1_str).unwrap();
-//     let matrix2 = Matrix::from_str(matrix2_str).unwrap();
+        let mut traces = Vec::with_capacity(k);
+        let mut power = self.clone();
+        for step in 0..k {
+            traces.push(power.trace()?);
+            if step + 1 < k {
+                power = (power * self.clone())?;
+            }
+        }
+        Ok(traces)
+    }
 
-//     println!("Matrix 1:\n{}", matrix1);
-//     println!("Matrix 2:\n{}", matrix2);
+    pub fn approx_eq(&self, other: &Matrix, tol: f64) -> bool {
+        self.rows == other.rows
+            && self.cols == other.cols
+            && self
+                .data
+                .iter()
+                .flatten()
+                .zip(other.data.iter().flatten())
+                .all(|(a, b)| (a - b).abs() <= tol)
+    }
 
-//     match matrix1.clone() + matrix2.clone() {
-//         Ok(result) => println!("Sum:\n{}", result),
-//         Err(e) => println!("Error: {}", e),
-//     }
+    pub fn is_orthogonal(&self, tol: f64) -> bool {
+        if self.rows != self.cols {
+            return false;
+        }
+        match self.transpose() * self.clone() {
+            Ok(product) => product.approx_eq(&Matrix::identity(self.rows), tol),
+            Err(_) => false,
+        }
+    }
 
-//     match matrix1.clone() - matrix2.clone() {
-//         Ok(result) => println!("Difference:\n{}", result),
-//         Err(e) => println!("Error: {}", e),
-//     }
+    // Формат: rows и cols как u64, затем элементы построчно как f64, всё в little-endian
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(16 + self.len() * 8);
+        bytes.extend_from_slice(&(self.rows as u64).to_le_bytes());
+        bytes.extend_from_slice(&(self.cols as u64).to_le_bytes());
+        for &val in self.data.iter().flatten() {
+            bytes.extend_from_slice(&val.to_le_bytes());
+        }
+        bytes
+    }
 
-//     match matrix1.clone() * matrix2.clone() {
-//         Ok(result) => println!("Multiply:\n{}", result),
-//         Err(e) => println!("Error: {}", e),
-//     }
+    pub fn from_bytes(b: &[u8]) -> Result<Matrix, String> {
+        if b.len() < 16 {
+            return Err("Недостаточно данных для заголовка матрицы".to_string());
+        }
 
-//     let matrix3_str = "1 2\n3 4";
-//     let matrix3 = Matrix::from_str(matrix3_str).unwrap();
+        let read_u64 = |offset: usize| {
+            let mut buf = [0u8; 8];
+            buf.copy_from_slice(&b[offset..offset + 8]);
+            u64::from_le_bytes(buf)
+        };
+        let rows = usize::try_from(read_u64(0)).map_err(|_| "Слишком большая размерность матрицы".to_string())?;
+        let cols = usize::try_from(read_u64(8)).map_err(|_| "Слишком большая размерность матрицы".to_string())?;
+        if cols == 0 && rows > MAX_EMPTY_ROWS {
+            return Err("Слишком большая размерность матрицы".to_string());
+        }
 
-//     println!("Matrix 3:\n{}", matrix3);
+        let expected_len = rows
+            .checked_mul(cols)
+            .and_then(|count| count.checked_mul(8))
+            .and_then(|size| size.checked_add(16))
+            .ok_or_else(|| "Слишком большая размерность матрицы".to_string())?;
+        if b.len() != expected_len {
+            return Err("Размер данных не соответствует размерности матрицы".to_string());
+        }
 
-//     match matrix3.determinant() {
-//         Ok(det) => println!("Determinant of Matrix 3: {}", det),
-//         Err(e) => println!("Error: {}", e),
-//     }
+        let mut result = Matrix::new(rows, cols);
+        for (idx, chunk) in b[16..].chunks_exact(8).enumerate() {
+            let mut buf = [0u8; 8];
+            buf.copy_from_slice(chunk);
+            result.data[idx / cols][idx % cols] = f64::from_le_bytes(buf);
+        }
+        Ok(result)
+    }
 
-//     println!("Transposed Matrix 3:\n{}", matrix3.transpose());
+    pub fn convolve2d(&self, kernel: &Matrix, mode: ConvMode) -> Result<Matrix, String> {
+        // Свёртка - это корреляция с ядром, повёрнутым на 180 градусов
+        let mut flipped = Matrix::new(kernel.rows, kernel.cols);
+        for i in 0..kernel.rows {
+            for j in 0..kernel.cols {
+                flipped.data[i][j] = kernel.data[kernel.rows - 1 - i][kernel.cols - 1 - j];
+            }
+        }
+        self.correlate2d(&flipped, mode)
+    }
 
-//     match matrix3.inverse() {
-//         Ok(inverse) => {
-//             println!("Inverse of Matrix 3:\n{}", inverse);
-//             match matrix3.clone() * inverse {
-//                 Ok(result) => println!("matrix one:\n{}", result),
-//                 Err(e) => println!("Error: {}", e),
-//             }
-//         }
-//         Err(e) => println!("Error: {}", e),
-//     }
+    pub fn correlate2d(&self, kernel: &Matrix, mode: ConvMode) -> Result<Matrix, String> {
+        if kernel.is_empty() {
+            return Err("Ядро свёртки пусто".to_string());
+        }
 
-//     let scaled_matrix = matrix3.clone() * 2.5; // Умножение на константу
-//     println!("Scaled Matrix 3 (by 2.5):\n{}", scaled_matrix);
+        let (kr, kc) = kernel.shape();
+        let padded = match mode {
+            ConvMode::Full => self.pad(kr - 1, kr - 1, kc - 1, kc - 1, 0.0),
+            ConvMode::Same => {
+                let (top, left) = ((kr - 1) / 2, (kc - 1) / 2);
+                self.pad(kr - 1 - top, top, kc - 1 - left, left, 0.0)
+            }
+            ConvMode::Valid => {
+                if kr > self.rows || kc > self.cols {
+                    return Err("Ядро свёртки больше матрицы".to_string());
+                }
+                self.clone()
+            }
+        };
 
-//     let system_str = "2 3 5 10\n1 -1 2 3\n3 2 -1 4";
-//     let system_matrix = Matrix::from_str(system_str).unwrap();
+        let mut result = Matrix::new(padded.rows - kr + 1, padded.cols - kc + 1);
+        for i in 0..result.rows {
+            for j in 0..result.cols {
+                let mut sum = 0.0;
+                for a in 0..kr {
+                    for b in 0..kc {
+                        sum += kernel.data[a][b] * padded.data[i + a][j + b];
+                    }
+                }
+                result.data[i][j] = sum;
+            }
+        }
+        Ok(result)
+    }
 
-//     println!("System of equations:\n{}", system_matrix);
+    pub fn is_upper_triangular(&self, tol: f64) -> bool {
+        self.rows == self.cols && (0..self.rows).all(|i| (0..i).all(|j| self.data[i][j].abs() <= tol))
+    }
 
-//     match system_matrix.gaussian_elimination() {
-//         Ok(solutions) => {
-//             println!("Solution using Gaussian elimination:");
-//             for (i, &x) in solutions.iter().enumerate() {
-//                 println!("x{} = {}", i + 1, x);
-//             }
-//         }
-//         Err(e) => println!("Error: {}", e),
-//     }
+    pub fn is_lower_triangular(&self, tol: f64) -> bool {
+        self.rows == self.cols && (0..self.rows).all(|i| (i + 1..self.cols).all(|j| self.data[i][j].abs() <= tol))
+    }
 
-//     match system_matrix.cramer_rule() {
-//         Ok(solutions) => {
-//             println!("Solution using Cramer's rule:");
-//             for (i, &x) in solutions.iter().enumerate() {
-//                 println!("x{} = {}", i + 1, x);
-//             }
-//         }
-//         Err(e) => println!("Error: {}", e),
-//     }
-// }
\ No newline at end of file
+    pub fn is_triangular(&self, tol: f64) -> bool {
+        self.is_upper_triangular(tol) || self.is_lower_triangular(tol)
+    }
+
+    // Обнуляет элементы вне ленты из lower поддиагоналей и upper наддиагоналей
+    pub fn band(&self, lower: usize, upper: usize) -> Matrix {
+        let mut result = self.clone();
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                if i > j + lower || j > i + upper {
+                    result.data[i][j] = 0.0;
+                }
+            }
+        }
+        result
+    }
+
+    // Фактическая ширина ленты: (число поддиагоналей, число наддиагоналей)
+    pub fn bandwidth(&self) -> (usize, usize) {
+        let (mut lower, mut upper) = (0, 0);
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                if self.data[i][j] != 0.0 {
+                    if i > j {
+                        lower = lower.max(i - j);
+                    } else {
+                        upper = upper.max(j - i);
+                    }
+                }
+            }
+        }
+        (lower, upper)
+    }
+
+    pub fn rows_iter(&self) -> impl Iterator<Item = &[f64]> {
+        self.data.iter().map(|row| row.as_slice())
+    }
+
+    pub fn cols_iter(&self) -> impl Iterator<Item = Vec<f64>> + '_ {
+        (0..self.cols).map(move |j| self.data.iter().map(|row| row[j]).collect())
+    }
+
+    // Сравнение построчно развёрнутых элементов без учёта формы, в отличие от approx_eq
+    pub fn flat_eq(&self, other: &Matrix, tol: f64) -> bool {
+        self.len() == other.len()
+            && self
+                .data
+                .iter()
+                .flatten()
+                .zip(other.data.iter().flatten())
+                .all(|(a, b)| (a - b).abs() <= tol)
+    }
+
+    // Вычисляет выражение вида "A * (B + C) - D" над именованными матрицами
+    pub fn evaluate(expr: &str, matrices: &HashMap<String, Matrix>) -> Result<Matrix, String> {
+        let mut parser = ExpressionParser {
+            tokens: tokenize(expr)?,
+            pos: 0,
+            depth: 0,
+            matrices,
+        };
+        let result = parser.parse_sum()?;
+        if parser.pos != parser.tokens.len() {
+            return Err("Лишние символы в конце выражения".to_string());
+        }
+        Ok(result)
+    }
+
+    // Решение L * U * x = P * b прямой и обратной подстановкой
+    fn lu_solve(lower: &Matrix, upper: &Matrix, permutation: &[usize], b: &[f64]) -> Vec<f64> {
+        let n = lower.rows;
+        let mut y = vec![0.0; n];
+        for i in 0..n {
+            y[i] = b[permutation[i]];
+            for k in 0..i {
+                y[i] -= lower.data[i][k] * y[k];
+            }
+        }
+
+        let mut x = vec![0.0; n];
+        for i in (0..n).rev() {
+            x[i] = y[i];
+            for k in i + 1..n {
+                x[i] -= upper.data[i][k] * x[k];
+            }
+            x[i] /= upper.data[i][i];
+        }
+        x
+    }
+
+    pub fn inverse_lu(&self) -> Result<Matrix, String> {
+        let (lower, upper, permutation) = self.lu_decomposition()?;
+        if (0..upper.rows).any(|i| upper.data[i][i] == 0.0) {
+            return Err("У данной матрицы нет обратной матрицы(определитель равен нулю)".to_string());
+        }
+
+        let n = self.rows;
+        let mut inverse = Matrix::new(n, n);
+        let mut unit = vec![0.0; n];
+        for j in 0..n {
+            unit[j] = 1.0;
+            let column = Matrix::lu_solve(&lower, &upper, &permutation, &unit);
+            for (i, value) in column.into_iter().enumerate() {
+                inverse.data[i][j] = value;
+            }
+            unit[j] = 0.0;
+        }
+        Ok(inverse)
+    }
+
+    // Обратная матрица через LU с итерационным уточнением X = X + X * (I - A * X).
+    // Невязка I - A * X почти целиком теряется при округлении в f64, поэтому она считается
+    // компенсированным суммированием; уточнение останавливается, как только невязка перестаёт уменьшаться
+    pub fn inverse_refined(&self, iterations: usize) -> Result<Matrix, String> {
+        let mut inverse = self.inverse_lu()?;
+        let mut correction = self.identity_residual(&inverse);
+        let mut residual = correction.frobenius_norm();
+        for _ in 0..iterations {
+            let candidate = (inverse.clone() + (inverse.clone() * correction)?)?;
+            let candidate_correction = self.identity_residual(&candidate);
+            let candidate_residual = candidate_correction.frobenius_norm();
+            if candidate_residual >= residual {
+                break;
+            }
+            inverse = candidate;
+            correction = candidate_correction;
+            residual = candidate_residual;
+        }
+        Ok(inverse)
+    }
+
+    // I - A * X с компенсированным скалярным произведением (алгоритм Dot2 Огиты-Румпа-Ойси):
+    // ошибки округления произведений и сумм накапливаются отдельно и добавляются в конце
+    fn identity_residual(&self, inverse: &Matrix) -> Matrix {
+        Matrix::from_fn(self.rows, inverse.cols, |i, j| {
+            let mut sum = if i == j { 1.0 } else { 0.0 };
+            let mut compensation = 0.0;
+            for k in 0..self.cols {
+                let product = -self.data[i][k] * inverse.data[k][j];
+                let product_error = (-self.data[i][k]).mul_add(inverse.data[k][j], -product);
+                let total = sum + product;
+                let rounded = total - sum;
+                let sum_error = (sum - (total - rounded)) + (product - rounded);
+                sum = total;
+                compensation += product_error + sum_error;
+            }
+            sum + compensation
+        })
+    }
+
+    pub fn row_sums(&self) -> Vec<f64> {
+        self.data.iter().map(|row| row.iter().sum()).collect()
+    }
+
+    pub fn col_sums(&self) -> Vec<f64> {
+        (0..self.cols).map(|j| self.data.iter().map(|row| row[j]).sum()).collect()
+    }
+
+    pub fn is_row_stochastic(&self, tol: f64) -> bool {
+        self.data.iter().flatten().all(|&x| x >= 0.0) && self.row_sums().iter().all(|s| (s - 1.0).abs() <= tol)
+    }
+
+    pub fn is_col_stochastic(&self, tol: f64) -> bool {
+        self.data.iter().flatten().all(|&x| x >= 0.0) && self.col_sums().iter().all(|s| (s - 1.0).abs() <= tol)
+    }
+
+    // Подматрица rows x cols, начинающаяся с элемента (row, col)
+    pub fn submatrix(&self, row: usize, col: usize, rows: usize, cols: usize) -> Matrix {
+        let mut result = Matrix::new(rows, cols);
+        for i in 0..rows {
+            result.data[i].copy_from_slice(&self.data[row + i][col..col + cols]);
+        }
+        result
+    }
+
+    // Дополнение Шура D - C * A^-1 * B для разбиения [[A, B], [C, D]] с блоком A размера split x split
+    pub fn schur_complement(&self, split: usize) -> Result<Matrix, String> {
+        if self.rows != self.cols {
+            return Err("Дополнение Шура определено только для квадратной матрицы".to_string());
+        }
+        if split == 0 || split >= self.rows {
+            return Err("Номер разбиения выходит за пределы матрицы".to_string());
+        }
+
+        let rest = self.rows - split;
+        let a = self.submatrix(0, 0, split, split);
+        let b = self.submatrix(0, split, split, rest);
+        let c = self.submatrix(split, 0, rest, split);
+        let d = self.submatrix(split, split, rest, rest);
+
+        let a_inverse = a.inverse_lu()?;
+        d - ((c * a_inverse)? * b)?
+    }
+
+    pub fn frobenius_norm(&self) -> f64 {
+        self.data.iter().flatten().map(|x| x * x).sum::<f64>().sqrt()
+    }
+
+    // Матричная экспонента рядом Тейлора I + A + A^2/2! + ...
+    pub fn exp(&self, terms: usize) -> Result<Matrix, String> {
+        if self.rows != self.cols {
+            return Err("Экспонента определена только для квадратной матрицы".to_string());
+        }
+
+        let mut result = Matrix::identity(self.rows);
+        let mut term = Matrix::identity(self.rows);
+        for k in 1..terms {
+            term = (term * self.clone())? * (1.0 / k as f64);
+            result = (result + term.clone())?;
+        }
+        Ok(result)
+    }
+
+    // Матричный логарифм рядом (A-I) - (A-I)^2/2 + (A-I)^3/3 - ...,
+    // сходимость гарантирована, когда норма A - I меньше единицы
+    pub fn log(&self, terms: usize) -> Result<Matrix, String> {
+        if self.rows != self.cols {
+            return Err("Логарифм определён только для квадратной матрицы".to_string());
+        }
+
+        let shifted = (self.clone() - Matrix::identity(self.rows))?;
+        if shifted.frobenius_norm() >= 1.0 {
+            return Err("Сходимость ряда для логарифма не гарантирована (матрица далека от единичной)".to_string());
+        }
+
+        let mut result = Matrix::new(self.rows, self.cols);
+        let mut power = Matrix::identity(self.rows);
+        for k in 1..=terms {
+            power = (power * shifted.clone())?;
+            let sign = if k % 2 == 1 { 1.0 } else { -1.0 };
+            result = (result + power.clone() * (sign / k as f64))?;
+        }
+        Ok(result)
+    }
+
+    pub fn is_symmetric(&self, tol: f64) -> bool {
+        self.rows == self.cols && (0..self.rows).all(|i| (0..i).all(|j| (self.data[i][j] - self.data[j][i]).abs() <= tol))
+    }
+
+    // Разложение Холецкого A = L * L^T для симметричной положительно определённой матрицы
+    pub fn cholesky(&self) -> Result<Matrix, String> {
+        // Порог симметрии масштабируется по величине элементов, как в symmetric_eigen
+        let scale = self.element_scale();
+        if !self.is_symmetric(ZERO_TOLERANCE * scale) {
+            return Err("Разложение Холецкого определено только для симметричной матрицы".to_string());
+        }
+
+        let n = self.rows;
+        let mut lower = Matrix::new(n, n);
+        for i in 0..n {
+            for j in 0..=i {
+                let mut sum = self.data[i][j];
+                for k in 0..j {
+                    sum -= lower.data[i][k] * lower.data[j][k];
+                }
+
+                if i == j {
+                    if sum <= 0.0 {
+                        return Err("Матрица не является положительно определённой".to_string());
+                    }
+                    lower.data[i][i] = sum.sqrt();
+                } else {
+                    lower.data[i][j] = sum / lower.data[j][j];
+                }
+            }
+        }
+        Ok(lower)
+    }
+
+    pub fn is_positive_definite(&self) -> bool {
+        self.cholesky().is_ok()
+    }
+
+    pub fn col_means(&self) -> Vec<f64> {
+        self.col_sums().into_iter().map(|s| s / self.rows as f64).collect()
+    }
+
+    // Выборочная дисперсия каждого столбца (знаменатель n - 1);
+    // для матрицы из одной строки дисперсия считается нулевой
+    pub fn col_variance(&self) -> Vec<f64> {
+        if self.rows < 2 {
+            return vec![0.0; self.cols];
+        }
+
+        let means = self.col_means();
+        (0..self.cols)
+            .map(|j| {
+                let squares: f64 = self.data.iter().map(|row| (row[j] - means[j]).powi(2)).sum();
+                squares / (self.rows - 1) as f64
+            })
+            .collect()
+    }
+
+    pub fn col_std(&self) -> Vec<f64> {
+        self.col_variance().into_iter().map(f64::sqrt).collect()
+    }
+
+    // Выборочная ковариационная матрица: строки - наблюдения, столбцы - переменные
+    pub fn covariance(&self) -> Matrix {
+        let mut result = Matrix::new(self.cols, self.cols);
+        if self.rows < 2 {
+            return result;
+        }
+
+        let means = self.col_means();
+        for a in 0..self.cols {
+            for b in a..self.cols {
+                let sum: f64 = self.data.iter().map(|row| (row[a] - means[a]) * (row[b] - means[b])).sum();
+                let cov = sum / (self.rows - 1) as f64;
+                result.data[a][b] = cov;
+                result.data[b][a] = cov;
+            }
+        }
+        result
+    }
+
+    // Собственные значения (по убыванию) и собственные векторы (по столбцам)
+    // симметричной матрицы, метод вращений Якоби
+    pub fn symmetric_eigen(&self) -> Result<(Vec<f64>, Matrix), String> {
+        const MAX_SWEEPS: usize = 100;
+
+        let scale = self.element_scale();
+        if !self.is_symmetric(ZERO_TOLERANCE * scale) {
+            return Err("Матрица должна быть симметричной".to_string());
+        }
+
+        let n = self.rows;
+        let mut a = self.clone();
+        let mut vectors = Matrix::identity(n);
+        let norm = self.frobenius_norm();
+        for _ in 0..MAX_SWEEPS {
+            let off_diagonal: f64 = (0..n)
+                .flat_map(|i| (0..n).filter(move |&j| j != i).map(move |j| (i, j)))
+                .map(|(i, j)| a.data[i][j] * a.data[i][j])
+                .sum();
+            if off_diagonal.sqrt() <= f64::EPSILON * norm {
+                break;
+            }
+
+            for p in 0..n {
+                for q in p + 1..n {
+                    if a.data[p][q] == 0.0 {
+                        continue;
+                    }
+
+                    // Поворот, обнуляющий элемент (p, q)
+                    let theta = (a.data[q][q] - a.data[p][p]) / (2.0 * a.data[p][q]);
+                    let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                    let c = 1.0 / (t * t + 1.0).sqrt();
+                    let s = t * c;
+
+                    for k in 0..n {
+                        let (akp, akq) = (a.data[k][p], a.data[k][q]);
+                        a.data[k][p] = c * akp - s * akq;
+                        a.data[k][q] = s * akp + c * akq;
+                    }
+                    for k in 0..n {
+                        let (apk, aqk) = (a.data[p][k], a.data[q][k]);
+                        a.data[p][k] = c * apk - s * aqk;
+                        a.data[q][k] = s * apk + c * aqk;
+                    }
+                    for k in 0..n {
+                        let (vkp, vkq) = (vectors.data[k][p], vectors.data[k][q]);
+                        vectors.data[k][p] = c * vkp - s * vkq;
+                        vectors.data[k][q] = s * vkp + c * vkq;
+                    }
+                }
+            }
+        }
+
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by(|&i, &j| a.data[j][j].total_cmp(&a.data[i][i]));
+
+        let values = order.iter().map(|&i| a.data[i][i]).collect();
+        let mut sorted_vectors = Matrix::new(n, n);
+        for (new_col, &old_col) in order.iter().enumerate() {
+            for k in 0..n {
+                sorted_vectors.data[k][new_col] = vectors.data[k][old_col];
+            }
+        }
+        Ok((values, sorted_vectors))
+    }
+
+    // Сингулярное разложение A = U * S * V^T через собственные векторы A^T * A;
+    // сингулярные значения по убыванию, U и V содержат min(rows, cols) столбцов.
+    // Переход к A^T * A возводит число обусловленности в квадрат, поэтому сингулярные значения
+    // меньше sqrt(eps) * sigma_max теряют точность - для них см. jacobi_singular_values
+    pub fn svd(&self) -> Result<(Matrix, Vec<f64>, Matrix), String> {
+        if self.is_empty() {
+            return Err("Матрица пуста".to_string());
+        }
+
+        let gram = (self.transpose() * self.clone())?.symmetrize()?;
+        let (eigenvalues, eigenvectors) = gram.symmetric_eigen()?;
+
+        // Собственные значения порядка eps * lambda_max неотличимы от погрешности вращений и считаются нулевыми
+        let (m, k) = (self.rows, self.rows.min(self.cols));
+        let floor = f64::EPSILON * self.cols as f64 * eigenvalues[0];
+        let singular_values: Vec<f64> = eigenvalues.iter().take(k).map(|&x| if x > floor { x.sqrt() } else { 0.0 }).collect();
+        let v = eigenvectors.submatrix(0, 0, self.cols, k);
+
+        // u_i = A * v_i / s_i; для нулевых сингулярных значений U дополняется ортонормированными векторами
+        let av = (self.clone() * v.clone())?;
+        let tolerance = ZERO_TOLERANCE * singular_values[0].max(1.0);
+        let mut u = Matrix::new(m, k);
+        for (i, &sigma) in singular_values.iter().enumerate() {
+            let mut column: Vec<f64> = if sigma > tolerance {
+                (0..m).map(|r| av.data[r][i] / sigma).collect()
+            } else {
+                vec![0.0; m]
+            };
+
+            let mut basis = 0;
+            while column.iter().map(|x| x * x).sum::<f64>() < 0.5 && basis < m {
+                column = vec![0.0; m];
+                column[basis] = 1.0;
+                for prev in 0..i {
+                    let dot: f64 = (0..m).map(|r| u.data[r][prev] * column[r]).sum();
+                    for (r, value) in column.iter_mut().enumerate() {
+                        *value -= dot * u.data[r][prev];
+                    }
+                }
+                let norm = column.iter().map(|x| x * x).sum::<f64>().sqrt();
+                if norm > 0.5 {
+                    column.iter_mut().for_each(|x| *x /= norm);
+                }
+                basis += 1;
+            }
+
+            for (r, value) in column.into_iter().enumerate() {
+                u.data[r][i] = value;
+            }
+        }
+
+        Ok((u, singular_values, v))
+    }
+
+    // Сингулярные значения по убыванию односторонним методом Якоби (Хестенса): столбцы A
+    // вращаются до взаимной ортогональности, и малые значения не теряются, как при переходе к A^T * A
+    fn jacobi_singular_values(&self) -> Result<Vec<f64>, String> {
+        const MAX_SWEEPS: usize = 100;
+
+        if self.is_empty() {
+            return Err("Матрица пуста".to_string());
+        }
+        if self.rows < self.cols {
+            return self.transpose().jacobi_singular_values();
+        }
+
+        let n = self.cols;
+        let mut w = self.clone();
+        for _ in 0..MAX_SWEEPS {
+            let mut rotated = false;
+            for p in 0..n {
+                for q in p + 1..n {
+                    let (mut alpha, mut beta, mut gamma) = (0.0, 0.0, 0.0);
+                    for row in &w.data {
+                        alpha += row[p] * row[p];
+                        beta += row[q] * row[q];
+                        gamma += row[p] * row[q];
+                    }
+                    if gamma == 0.0 || gamma.abs() <= f64::EPSILON * (alpha * beta).sqrt() {
+                        continue;
+                    }
+                    rotated = true;
+
+                    // Поворот, делающий столбцы p и q ортогональными
+                    let zeta = (beta - alpha) / (2.0 * gamma);
+                    let t = zeta.signum() / (zeta.abs() + (zeta * zeta + 1.0).sqrt());
+                    let c = 1.0 / (t * t + 1.0).sqrt();
+                    let s = t * c;
+                    for row in w.data.iter_mut() {
+                        let (xp, xq) = (row[p], row[q]);
+                        row[p] = c * xp - s * xq;
+                        row[q] = s * xp + c * xq;
+                    }
+                }
+            }
+            if !rotated {
+                break;
+            }
+        }
+
+        let mut norms: Vec<f64> = (0..n).map(|j| w.data.iter().map(|row| row[j] * row[j]).sum::<f64>().sqrt()).collect();
+        norms.sort_by(|a, b| b.total_cmp(a));
+        Ok(norms)
+    }
+
+    // Ранг по сингулярным значениям, вычисленным по самой A: малые значения, теряемые в A^T * A, учитываются
+    pub fn rank_svd(&self, tol: f64) -> Result<usize, String> {
+        let singular_values = self.jacobi_singular_values()?;
+        Ok(singular_values.iter().filter(|&&s| s > tol).count())
+    }
+
+    pub fn to_latex(&self) -> String {
+        let rows: Vec<String> = self
+            .data
+            .iter()
+            .map(|row| row.iter().map(|val| val.to_string()).collect::<Vec<_>>().join(" & "))
+            .collect();
+        format!("\\begin{{bmatrix}}\n{}\n\\end{{bmatrix}}", rows.join(" \\\\\n"))
+    }
+
+    // Прямой ход метода Гаусса по шагам: описание операции и матрица после неё;
+    // вырожденная система отклоняется, а не доводится до деления на нулевой ведущий элемент
+    pub fn gaussian_elimination_steps(&self) -> Result<Vec<(String, Matrix)>, String> {
+        let mut steps = Vec::new();
+        self.solve_with_steps(&mut steps)?;
+        Ok(steps)
+    }
+
+    pub fn gaussian_elimination_latex(&self) -> Result<String, String> {
+        let mut steps = Vec::new();
+        let solutions = self.solve_with_steps(&mut steps)?;
+
+        let mut latex = String::new();
+        for (label, matrix) in &steps {
+            latex.push_str(&format!("{label}:\n\\[\n{}\n\\]\n", matrix.to_latex()));
+        }
+        latex.push_str("Решение:\n\\[\n");
+        let values: Vec<String> = solutions.iter().enumerate().map(|(i, x)| format!("x_{{{}}} = {}", i + 1, x)).collect();
+        latex.push_str(&values.join(",\\quad "));
+        latex.push_str("\n\\]\n");
+        Ok(latex)
+    }
+
+    // Решение системы; шаги того же прохода исключения дописываются в steps
+    fn solve_with_steps(&self, steps: &mut Vec<(String, Matrix)>) -> Result<Vec<f64>, String> {
+        if self.rows + 1 != self.cols {
+            return Err("У матрицы неправильная размерность для метода Гаусса".to_string());
+        }
+
+        steps.push(("Исходная матрица".to_string(), self.clone()));
+        let (echelon, _) = self.forward_elimination(|label, matrix| steps.push((label, matrix.clone())));
+
+        let tolerance = ZERO_TOLERANCE * self.element_scale();
+        if (0..self.rows).any(|i| !echelon.data[i][i].is_finite() || echelon.data[i][i].abs() <= tolerance) {
+            return Err("Система не имеет единственного решения(определитель равен нулю)".to_string());
+        }
+
+        Ok(Matrix::back_substitution(&echelon))
+    }
+
+    // Предобуславливатель Якоби: диагональная матрица из обратных диагональных элементов
+    pub fn diagonal_inverse(&self) -> Result<Matrix, String> {
+        if self.rows != self.cols {
+            return Err("Обратная диагональ определена только для квадратной матрицы".to_string());
+        }
+
+        let mut result = Matrix::new(self.rows, self.cols);
+        for i in 0..self.rows {
+            if self.data[i][i] == 0.0 {
+                return Err("На диагонали матрицы есть нулевой элемент".to_string());
+            }
+            result.data[i][i] = 1.0 / self.data[i][i];
+        }
+        Ok(result)
+    }
+
+    pub fn row(&self, i: usize) -> Result<Vec<f64>, String> {
+        if i >= self.rows {
+            return Err("Номер строки выходит за пределы матрицы".to_string());
+        }
+        Ok(self.data[i].clone())
+    }
+
+    pub fn col(&self, j: usize) -> Result<Vec<f64>, String> {
+        if j >= self.cols {
+            return Err("Номер столбца выходит за пределы матрицы".to_string());
+        }
+        Ok(self.data.iter().map(|row| row[j]).collect())
+    }
+
+    pub fn set_row(&mut self, i: usize, values: &[f64]) -> Result<(), String> {
+        if i >= self.rows {
+            return Err("Номер строки выходит за пределы матрицы".to_string());
+        }
+        if values.len() != self.cols {
+            return Err("Длина строки не совпадает с числом столбцов матрицы".to_string());
+        }
+        self.data[i].copy_from_slice(values);
+        Ok(())
+    }
+
+    pub fn set_col(&mut self, j: usize, values: &[f64]) -> Result<(), String> {
+        if j >= self.cols {
+            return Err("Номер столбца выходит за пределы матрицы".to_string());
+        }
+        if values.len() != self.rows {
+            return Err("Длина столбца не совпадает с числом строк матрицы".to_string());
+        }
+        for (row, &val) in self.data.iter_mut().zip(values) {
+            row[j] = val;
+        }
+        Ok(())
+    }
+
+    pub fn remove_row(&self, i: usize) -> Result<Matrix, String> {
+        if i >= self.rows {
+            return Err("Номер строки выходит за пределы матрицы".to_string());
+        }
+        let mut result = self.clone();
+        result.data.remove(i);
+        result.rows -= 1;
+        Ok(result)
+    }
+
+    pub fn remove_col(&self, j: usize) -> Result<Matrix, String> {
+        if j >= self.cols {
+            return Err("Номер столбца выходит за пределы матрицы".to_string());
+        }
+        let mut result = self.clone();
+        for row in result.data.iter_mut() {
+            row.remove(j);
+        }
+        result.cols -= 1;
+        Ok(result)
+    }
+
+    // Псевдообратная матрица Мура-Пенроуза V * S^+ * U^T; сингулярные значения не больше tol считаются нулевыми
+    pub fn pseudoinverse_svd(&self, tol: f64) -> Result<Matrix, String> {
+        let (u, singular_values, v) = self.svd()?;
+
+        let mut result = Matrix::new(self.cols, self.rows);
+        for (k, &sigma) in singular_values.iter().enumerate() {
+            if sigma <= tol {
+                continue;
+            }
+            for i in 0..self.cols {
+                for j in 0..self.rows {
+                    result.data[i][j] += v.data[i][k] * u.data[j][k] / sigma;
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    // Метод Гаусса с проверкой ведущих элементов на близость к нулю
+    pub fn solve(&self) -> Result<SolveReport, String> {
+        let (solutions, echelon, _) = self.gaussian_elimination_with_permutation()?;
+
+        let ratio = Matrix::pivot_ratio(&echelon);
+        if ratio == 0.0 {
+            return Err("Система не имеет единственного решения(нулевой ведущий элемент)".to_string());
+        }
+
+        let warning = if ratio < NEAR_SINGULAR_PIVOT_RATIO {
+            Some(format!("Система близка к вырожденной: отношение ведущих элементов {ratio:e}, решение может быть неточным"))
+        } else {
+            None
+        };
+
+        Ok(SolveReport { solutions, warning })
+    }
+
+    // Отношение минимального по модулю диагонального (ведущего) элемента к максимальному
+    fn pivot_ratio(upper: &Matrix) -> f64 {
+        let pivots: Vec<f64> = (0..upper.rows).map(|i| upper.data[i][i].abs()).collect();
+        let max_pivot = pivots.iter().copied().fold(0.0, f64::max);
+        let min_pivot = pivots.iter().copied().fold(f64::INFINITY, f64::min);
+        if max_pivot == 0.0 {
+            return 0.0;
+        }
+        min_pivot / max_pivot
+    }
+
+    pub fn map(&self, f: impl Fn(f64) -> f64) -> Matrix {
+        Matrix {
+            rows: self.rows,
+            cols: self.cols,
+            data: self.data.iter().map(|row| row.iter().map(|&x| f(x)).collect()).collect(),
+        }
+    }
+
+    // Поэлементная степень (не путать с матричной pow); отрицательные числа
+    // в нецелой степени дают NaN, как и f64::powf
+    pub fn powf_elementwise(&self, exponent: f64) -> Matrix {
+        self.map(|x| x.powf(exponent))
+    }
+
+    // Скалярное произведение Фробениуса: сумма произведений соответствующих элементов
+    pub fn frobenius_inner(&self, other: &Matrix) -> Result<f64, String> {
+        if self.rows != other.rows || self.cols != other.cols {
+            return Err("Матрицы разной размерности".to_string());
+        }
+        Ok(self.data.iter().flatten().zip(other.data.iter().flatten()).map(|(a, b)| a * b).sum())
+    }
+
+    // Разреженное представление (строка, столбец, значение) без элементов, не превышающих tol по модулю
+    pub fn to_triplets(&self, tol: f64) -> Vec<(usize, usize, f64)> {
+        let mut triplets = Vec::new();
+        for (i, row) in self.data.iter().enumerate() {
+            for (j, &val) in row.iter().enumerate() {
+                if val.abs() > tol {
+                    triplets.push((i, j, val));
+                }
+            }
+        }
+        triplets
+    }
+
+    // Решение системы матричным методом x = A^-1 * b
+    pub fn solve_by_inverse(&self) -> Result<Vec<f64>, String> {
+        if self.rows + 1 != self.cols {
+            return Err("У матрицы неправильная размерность для матричного метода".to_string());
+        }
+
+        let n = self.rows;
+        let core_matrix = self.submatrix(0, 0, n, n);
+        let free_terms = self.submatrix(0, n, n, 1);
+        let solution = (core_matrix.inverse()? * free_terms)?;
+        Ok(solution.to_vec())
+    }
+
+    // Вектор - матрица из одной строки или одного столбца
+    pub fn is_vector(&self) -> bool {
+        (self.rows == 1 || self.cols == 1) && !self.is_empty()
+    }
+
+    pub fn dot(&self, other: &Matrix) -> Result<f64, String> {
+        if !self.is_vector() || !other.is_vector() {
+            return Err("Скалярное произведение определено только для векторов".to_string());
+        }
+        if self.len() != other.len() {
+            return Err("Векторы разной длины".to_string());
+        }
+        // Строка и столбец одной длины приводятся к одной форме
+        if self.shape() == other.shape() {
+            self.frobenius_inner(other)
+        } else {
+            self.frobenius_inner(&other.transpose())
+        }
+    }
+
+    // Угол между векторами в радианах
+    pub fn angle_between(&self, other: &Matrix) -> Result<f64, String> {
+        let dot = self.dot(other)?;
+        let norms = self.frobenius_norm() * other.frobenius_norm();
+        if norms == 0.0 {
+            return Err("Угол с нулевым вектором не определён".to_string());
+        }
+        // Ограничиваем косинус, чтобы погрешность округления не дала NaN в acos
+        Ok((dot / norms).clamp(-1.0, 1.0).acos())
+    }
+
+    // Векторное произведение трёхмерных векторов, результат - вектор-столбец
+    pub fn cross(&self, other: &Matrix) -> Result<Matrix, String> {
+        if !self.is_vector() || !other.is_vector() || self.len() != 3 || other.len() != 3 {
+            return Err("Векторное произведение определено только для трёхмерных векторов".to_string());
+        }
+
+        let a = self.to_vec();
+        let b = other.to_vec();
+        Ok(Matrix {
+            rows: 3,
+            cols: 1,
+            data: vec![
+                vec![a[1] * b[2] - a[2] * b[1]],
+                vec![a[2] * b[0] - a[0] * b[2]],
+                vec![a[0] * b[1] - a[1] * b[0]],
+            ],
+        })
+    }
+
+    fn integer_entries(&self) -> Result<Vec<Vec<i64>>, String> {
+        // Целые числа, точно представимые в f64
+        const MAX_EXACT: f64 = 9007199254740992.0;
+        self.data
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|&x| {
+                        if x.fract() == 0.0 && x.abs() <= MAX_EXACT {
+                            Ok(x as i64)
+                        } else {
+                            Err("Элементы матрицы должны быть целыми числами".to_string())
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    pub fn modulo(&self, m: i64) -> Result<Matrix, String> {
+        if m <= 0 {
+            return Err("Модуль должен быть положительным".to_string());
+        }
+
+        let entries = self.integer_entries()?;
+        Ok(Matrix {
+            rows: self.rows,
+            cols: self.cols,
+            data: entries
+                .iter()
+                .map(|row| row.iter().map(|x| x.rem_euclid(m) as f64).collect())
+                .collect(),
+        })
+    }
+
+    // Определитель в кольце вычетов по модулю m; строки приводятся алгоритмом Евклида,
+    // поэтому модуль не обязан быть простым
+    pub fn determinant_mod(&self, m: i64) -> Result<i64, String> {
+        if self.rows != self.cols {
+            return Err("Определитель может быть вычислен только для квадратной матрицы".to_string());
+        }
+
+        let mut a: Vec<Vec<i128>> = self
+            .modulo(m)?
+            .data
+            .iter()
+            .map(|row| row.iter().map(|&x| x as i128).collect())
+            .collect();
+        let m = m as i128;
+        let n = self.rows;
+
+        let mut det: i128 = 1 % m;
+        for col in 0..n {
+            for row in col + 1..n {
+                while a[row][col] != 0 {
+                    let q = a[col][col] / a[row][col];
+                    let (head, tail) = a.split_at_mut(row);
+                    for (target, &value) in head[col][col..].iter_mut().zip(&tail[0][col..]) {
+                        *target = (*target - q * value).rem_euclid(m);
+                    }
+                    a.swap(col, row);
+                    det = (-det).rem_euclid(m);
+                }
+            }
+            det = det * a[col][col] % m;
+        }
+        Ok(det as i64)
+    }
+
+    // Обратная матрица по модулю m: adj(A) * det^-1 mod m
+    pub fn inverse_mod(&self, m: i64) -> Result<Matrix, String> {
+        let det = self.determinant_mod(m)?;
+        let det_inverse = mod_inverse(det, m)
+            .ok_or_else(|| "Определитель не обратим по данному модулю".to_string())?;
+
+        let n = self.rows;
+        let reduced = self.modulo(m)?;
+        if n == 1 {
+            return Ok(Matrix {
+                rows: 1,
+                cols: 1,
+                data: vec![vec![det_inverse as f64]],
+            });
+        }
+
+        let mut inverse = Matrix::new(n, n);
+        for i in 0..n {
+            for j in 0..n {
+                let minor = reduced.minor_matrix(i, j).determinant_mod(m)? as i128;
+                let cofactor = if (i + j) % 2 == 1 { -minor } else { minor };
+                // Транспонирование: элемент присоединённой матрицы (j, i)
+                inverse.data[j][i] = (cofactor * det_inverse as i128).rem_euclid(m as i128) as f64;
+            }
+        }
+        Ok(inverse)
+    }
+
+    // Ядерная норма - сумма сингулярных значений
+    pub fn nuclear_norm(&self) -> Result<f64, String> {
+        let (_, singular_values, _) = self.svd()?;
+        Ok(singular_values.iter().sum())
+    }
+
+    // Спектральная норма - наибольшее сингулярное значение
+    pub fn spectral_norm(&self) -> Result<f64, String> {
+        let (_, singular_values, _) = self.svd()?;
+        Ok(singular_values[0])
+    }
+
+    // Линейная интерполяция (1 - t) * self + t * other; при t вне [0, 1] - экстраполяция
+    pub fn lerp(&self, other: &Matrix, t: f64) -> Result<Matrix, String> {
+        self.clone() * (1.0 - t) + other.clone() * t
+    }
+
+    // Норма Фробениуса ||A * A^-1 - I||: чем ближе к нулю, тем надёжнее обратная матрица
+    pub fn inverse_accuracy(&self) -> Result<f64, String> {
+        let inverse = self.inverse()?;
+        let residual = ((self.clone() * inverse)? - Matrix::identity(self.rows))?;
+        Ok(residual.frobenius_norm())
+    }
+
+    // Точный определитель целочисленной матрицы алгоритмом Барейса (деления в нём всегда нацело)
+    pub fn determinant_bareiss(&self) -> Result<f64, String> {
+        if self.rows != self.cols {
+            return Err("Определитель может быть вычислен только для квадратной матрицы".to_string());
+        }
+
+        let n = self.rows;
+        if n == 0 {
+            return Ok(1.0);
+        }
+
+        let overflow = || "Переполнение при вычислении определителя".to_string();
+        let mut a: Vec<Vec<i128>> = self
+            .integer_entries()?
+            .iter()
+            .map(|row| row.iter().map(|&x| x as i128).collect())
+            .collect();
+
+        let mut sign = 1;
+        let mut prev_pivot: i128 = 1;
+        for k in 0..n - 1 {
+            if a[k][k] == 0 {
+                match (k + 1..n).find(|&i| a[i][k] != 0) {
+                    Some(i) => {
+                        a.swap(k, i);
+                        sign = -sign;
+                    }
+                    None => return Ok(0.0),
+                }
+            }
+
+            for i in k + 1..n {
+                for j in k + 1..n {
+                    let value = a[i][j]
+                        .checked_mul(a[k][k])
+                        .zip(a[i][k].checked_mul(a[k][j]))
+                        .and_then(|(x, y)| x.checked_sub(y))
+                        .ok_or_else(overflow)?;
+                    a[i][j] = value / prev_pivot;
+                }
+            }
+            prev_pivot = a[k][k];
+        }
+
+        Ok((sign * a[n - 1][n - 1]) as f64)
+    }
+
+    // Квадратный корень симметричной положительно определённой матрицы: V * sqrt(D) * V^T
+    pub fn sqrt(&self) -> Result<Matrix, String> {
+        if !self.is_positive_definite() {
+            return Err("Квадратный корень определён только для симметричной положительно определённой матрицы".to_string());
+        }
+
+        let (eigenvalues, eigenvectors) = self.symmetric_eigen()?;
+        let n = self.rows;
+        let mut result = Matrix::new(n, n);
+        for (k, &lambda) in eigenvalues.iter().enumerate() {
+            let root = lambda.max(0.0).sqrt();
+            for i in 0..n {
+                for j in 0..n {
+                    result.data[i][j] += root * eigenvectors.data[i][k] * eigenvectors.data[j][k];
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    // Главная диагональ (для прямоугольной матрицы - первые min(rows, cols) элементов)
+    pub fn diagonal(&self) -> Vec<f64> {
+        (0..self.rows.min(self.cols)).map(|i| self.data[i][i]).collect()
+    }
+
+    // Побочная диагональ от правого верхнего угла к левому нижнему;
+    // для неквадратной матрицы возвращается пустой вектор
+    pub fn anti_diagonal(&self) -> Vec<f64> {
+        if self.rows != self.cols {
+            return Vec::new();
+        }
+        (0..self.rows).map(|i| self.data[i][self.cols - 1 - i]).collect()
+    }
+
+    // Повторение матрицы vertical раз вниз и horizontal раз вправо
+    pub fn tile(&self, vertical: usize, horizontal: usize) -> Matrix {
+        Matrix::from_fn(self.rows * vertical, self.cols * horizontal, |i, j| {
+            self.data[i % self.rows][j % self.cols]
+        })
+    }
+
+    // Поэлементно обратные значения 1 / x (не путать с обратной матрицей inverse)
+    pub fn reciprocal(&self) -> Result<Matrix, String> {
+        if self.data.iter().flatten().any(|&x| x == 0.0) {
+            return Err("В матрице есть нулевой элемент".to_string());
+        }
+        Ok(self.map(|x| 1.0 / x))
+    }
+
+    // То же, но нулевые элементы остаются нулями
+    pub fn reciprocal_safe(&self) -> Matrix {
+        self.map(|x| if x == 0.0 { 0.0 } else { 1.0 / x })
+    }
+
+    pub fn commutes_with(&self, other: &Matrix, tol: f64) -> Result<bool, String> {
+        if self.rows != self.cols || other.rows != other.cols || self.rows != other.rows {
+            return Err("Коммутативность проверяется только для квадратных матриц одного размера".to_string());
+        }
+        let ab = (self.clone() * other.clone())?;
+        let ba = (other.clone() * self.clone())?;
+        Ok(ab.approx_eq(&ba, tol))
+    }
+
+    // Коммутатор [A, B] = A * B - B * A
+    pub fn commutator(&self, other: &Matrix) -> Result<Matrix, String> {
+        if self.rows != self.cols || other.rows != other.cols || self.rows != other.rows {
+            return Err("Коммутатор определён только для квадратных матриц одного размера".to_string());
+        }
+        (self.clone() * other.clone())? - (other.clone() * self.clone())?
+    }
+
+    // Симметричная и кососимметричная части за одно транспонирование
+    pub fn decompose_symmetric(&self) -> Result<(Matrix, Matrix), String> {
+        if self.rows != self.cols {
+            return Err("Разложение на симметричную и кососимметричную части определено только для квадратной матрицы".to_string());
+        }
+        let transposed = self.transpose();
+        let symmetric = (self.clone() + transposed.clone())? * 0.5;
+        let skew = (self.clone() - transposed)? * 0.5;
+        Ok((symmetric, skew))
+    }
+
+    // Объединение по вертикали: строки other добавляются под строками self
+    pub fn vstack(&self, other: &Matrix) -> Result<Matrix, String> {
+        if self.cols != other.cols {
+            return Err("У матриц разное количество столбцов".to_string());
+        }
+        let mut result = self.clone();
+        result.data.extend(other.data.iter().cloned());
+        result.rows += other.rows;
+        Ok(result)
+    }
+
+    // Объединение по горизонтали: столбцы other добавляются справа от столбцов self
+    pub fn hstack(&self, other: &Matrix) -> Result<Matrix, String> {
+        if self.rows != other.rows {
+            return Err("У матриц разное количество строк".to_string());
+        }
+        let mut result = self.clone();
+        for (row, other_row) in result.data.iter_mut().zip(&other.data) {
+            row.extend_from_slice(other_row);
+        }
+        result.cols += other.cols;
+        Ok(result)
+    }
+
+    pub fn concat(&self, other: &Matrix, axis: Axis) -> Result<Matrix, String> {
+        match axis {
+            Axis::Row => self
+                .vstack(other)
+                .map_err(|_| "Нельзя объединить матрицы по строкам (Axis::Row): разное количество столбцов".to_string()),
+            Axis::Col => self
+                .hstack(other)
+                .map_err(|_| "Нельзя объединить матрицы по столбцам (Axis::Col): разное количество строк".to_string()),
+        }
+    }
+
+    // Угловые миноры: определители левых верхних подматриц k x k, k = 1..n
+    pub fn leading_minors(&self) -> Result<Vec<f64>, String> {
+        if self.rows != self.cols {
+            return Err("Угловые миноры определены только для квадратной матрицы".to_string());
+        }
+        (1..=self.rows).map(|k| self.submatrix(0, 0, k, k).determinant_lu()).collect()
+    }
+
+    // Собственное вращение: ортогональная матрица с определителем +1 (отражения дают -1)
+    pub fn is_rotation(&self, tol: f64) -> bool {
+        self.is_orthogonal(tol) && matches!(self.determinant_lu(), Ok(det) if (det - 1.0).abs() <= tol)
+    }
+
+    // Ранг с относительным порогом: ведущий элемент учитывается, если он больше rel_tol,
+    // умноженного на наибольший ведущий элемент
+    pub fn rank_tolerant(&self, rel_tol: f64) -> usize {
+        let mut largest = 0.0_f64;
+        let (reduced, pivot_cols) = self.row_echelon_with(self.cols, |candidate| {
+            if candidate == 0.0 || candidate <= rel_tol * largest {
+                return false;
+            }
+            largest = largest.max(candidate);
+            true
+        });
+
+        // Ранние ведущие элементы могли оказаться малыми относительно найденного позже наибольшего
+        pivot_cols
+            .iter()
+            .enumerate()
+            .filter(|&(row, &col)| reduced.data[row][col].abs() > rel_tol * largest)
+            .count()
+    }
+
+    // Обратная перестановка: если perm[i] = j, то результат[j] = i
+    pub fn invert_permutation(perm: &[usize]) -> Vec<usize> {
+        let mut inverse = vec![0; perm.len()];
+        for (i, &j) in perm.iter().enumerate() {
+            inverse[j] = i;
+        }
+        inverse
+    }
+
+    // Знак определителя (-1, 0 или +1) по LU: чётность перестановки строк и знаки ведущих элементов
+    pub fn determinant_sign(&self) -> Result<i8, String> {
+        let (_, upper, permutation) = self.lu_decomposition()?;
+
+        let mut sign: i8 = if Matrix::permutation_sign(&permutation) < 0.0 { -1 } else { 1 };
+        for i in 0..upper.rows {
+            let pivot = upper.data[i][i];
+            if pivot == 0.0 {
+                return Ok(0);
+            }
+            if pivot < 0.0 {
+                sign = -sign;
+            }
+        }
+        Ok(sign)
+    }
+
+    // Элементы, удовлетворяющие условию, в порядке обхода по строкам
+    pub fn collect_where<F: Fn(f64) -> bool>(&self, pred: F) -> Vec<f64> {
+        self.data.iter().flatten().copied().filter(|&x| pred(x)).collect()
+    }
+
+    // Проверка разложения P * A = L * U, где perm[i] - исходный номер i-й строки P * A
+    pub fn verify_lu(&self, l: &Matrix, u: &Matrix, perm: &[usize], tol: f64) -> bool {
+        let n = self.rows;
+        if self.cols != n || l.rows != n || l.cols != n || u.rows != n || u.cols != n || perm.len() != n {
+            return false;
+        }
+
+        let mut seen = vec![false; n];
+        for &p in perm {
+            if p >= n || seen[p] {
+                return false;
+            }
+            seen[p] = true;
+        }
+
+        let permuted = Matrix {
+            rows: n,
+            cols: n,
+            data: perm.iter().map(|&p| self.data[p].clone()).collect(),
+        };
+        match l.clone() * u.clone() {
+            Ok(product) => permuted.approx_eq(&product, tol),
+            Err(_) => false,
+        }
+    }
+
+    // Поэлементный знак; в отличие от f64::signum, ноль (в т.ч. -0.0) переходит в 0.0
+    pub fn signum(&self) -> Matrix {
+        self.map(|x| if x == 0.0 { 0.0 } else { x.signum() })
+    }
+
+    // Пары строк (i, j), i < j, одна из которых кратна другой с точностью tol;
+    // нулевая строка считается кратной любой
+    pub fn proportional_rows(&self, tol: f64) -> Vec<(usize, usize)> {
+        // Строки без элементов нулевые, а значит кратны друг другу
+        if self.cols == 0 {
+            return (0..self.rows).flat_map(|i| (i + 1..self.rows).map(move |j| (i, j))).collect();
+        }
+
+        // total_cmp не паникует на NaN; строка с NaN не окажется кратной никакой другой
+        let max_index = |row: &[f64]| {
+            (0..row.len())
+                .max_by(|&a, &b| row[a].abs().total_cmp(&row[b].abs()))
+                .unwrap_or(0)
+        };
+
+        let mut pairs = Vec::new();
+        for i in 0..self.rows {
+            for j in i + 1..self.rows {
+                // Основой служит строка с наибольшим по модулю элементом
+                let (base, other) = {
+                    let (a, b) = (&self.data[i], &self.data[j]);
+                    if a[max_index(a)].abs() >= b[max_index(b)].abs() { (a, b) } else { (b, a) }
+                };
+                let k = max_index(base);
+                if base[k].abs() <= tol {
+                    pairs.push((i, j));
+                    continue;
+                }
+
+                let factor = other[k] / base[k];
+                if base.iter().zip(other).all(|(x, y)| (y - factor * x).abs() <= tol) {
+                    pairs.push((i, j));
+                }
+            }
+        }
+        pairs
+    }
+
+    // Решение задачи наименьших квадратов через нормальные уравнения A^T * A * x = A^T * b;
+    // каждый столбец b - отдельная правая часть
+    pub fn solve_least_squares(&self, b: &Matrix) -> Result<Matrix, String> {
+        self.solve_weighted_least_squares(b, &vec![1.0; self.rows])
+    }
+
+    // Взвешенные нормальные уравнения A^T * W * A * x = A^T * W * b, где W = diag(weights)
+    pub fn solve_weighted_least_squares(&self, b: &Matrix, weights: &[f64]) -> Result<Matrix, String> {
+        if b.rows != self.rows {
+            return Err("Количество строк правой части не совпадает с количеством уравнений".to_string());
+        }
+        if weights.len() != self.rows {
+            return Err("Количество весов не совпадает с количеством уравнений".to_string());
+        }
+        if weights.iter().any(|&w| w <= 0.0) {
+            return Err("Веса должны быть положительными".to_string());
+        }
+
+        let n = self.cols;
+        let mut normal = Matrix::new(n, n);
+        for i in 0..n {
+            for j in 0..=i {
+                let sum: f64 = (0..self.rows).map(|k| weights[k] * self.data[k][i] * self.data[k][j]).sum();
+                // Заполняем симметрично, чтобы проверка симметрии в cholesky не зависела от округлений
+                normal.data[i][j] = sum;
+                normal.data[j][i] = sum;
+            }
+        }
+
+        let mut rhs = Matrix::new(n, b.cols);
+        for i in 0..n {
+            for c in 0..b.cols {
+                rhs.data[i][c] = (0..self.rows).map(|k| weights[k] * self.data[k][i] * b.data[k][c]).sum();
+            }
+        }
+
+        // При неполном ранге столбцов A матрица нормальных уравнений вырождена
+        let lower = normal
+            .cholesky()
+            .map_err(|_| "Матрица системы имеет неполный ранг".to_string())?;
+
+        let mut result = Matrix::new(n, b.cols);
+        for c in 0..b.cols {
+            // Прямой ход L * y = rhs, затем обратный L^T * x = y
+            let mut y = vec![0.0; n];
+            for i in 0..n {
+                let sum: f64 = (0..i).map(|k| lower.data[i][k] * y[k]).sum();
+                y[i] = (rhs.data[i][c] - sum) / lower.data[i][i];
+            }
+            for i in (0..n).rev() {
+                let sum: f64 = (i + 1..n).map(|k| lower.data[k][i] * result.data[k][c]).sum();
+                result.data[i][c] = (y[i] - sum) / lower.data[i][i];
+            }
+        }
+        Ok(result)
+    }
+
+    // Присоединённая матрица: транспонированная матрица алгебраических дополнений
+    pub fn adjugate(&self) -> Result<Matrix, String> {
+        let mut cofactors = Matrix::new(self.rows, self.cols);
+        for i in 0..self.rows {
+            cofactors.data[i] = self.row_cofactors(i)?;
+        }
+        Ok(cofactors.transpose())
+    }
+
+    // В отличие от inverse, всегда идёт через присоединённую матрицу, чтобы показать формулу целиком
+    pub fn inverse_adjugate(&self) -> Result<AdjugateInverse, String> {
+        let determinant = self.determinant()?;
+        if determinant == 0.0 {
+            return Err("У данной матрицы нет обратной матрицы(определитель равен нулю)".to_string());
+        }
+
+        let adjugate = self.adjugate()?;
+        Ok(AdjugateInverse {
+            inverse: adjugate.clone() * (1.0 / determinant),
+            adjugate,
+            determinant,
+        })
+    }
+
+    // Определитель в поле GF(p); для простого модуля определитель в кольце вычетов и есть определитель в поле
+    pub fn determinant_mod_prime(&self, p: u64) -> Result<u64, String> {
+        if !is_prime(p) {
+            return Err("Модуль должен быть простым числом".to_string());
+        }
+        let m = i64::try_from(p).map_err(|_| "Модуль слишком велик".to_string())?;
+        Ok(self.determinant_mod(m)? as u64)
+    }
+
+    // Разбор одной строки ввода; line - номер строки (с единицы) для сообщений об ошибках
+    fn parse_row(row_str: &str, line: usize) -> Result<Vec<f64>, String> {
+        row_str
+            .split_whitespace()
+            .enumerate()
+            .map(|(j, s)| {
+                s.parse()
+                    .map_err(|_| format!("Неправильное значение '{}' в строке {}, столбце {}", s, line, j + 1))
+            })
+            .collect()
+    }
+
+    // Построчное чтение без загрузки всего ввода в одну строку; пустые строки в начале
+    // и в конце пропускаются, а пустая строка между строками матрицы считается ошибкой
+    pub fn from_reader<R: BufRead>(reader: R) -> Result<Matrix, String> {
+        let mut data: Vec<Vec<f64>> = Vec::new();
+        let mut first_blank = None;
+        for (i, line) in reader.lines().enumerate() {
+            let line = line.map_err(|e| format!("Ошибка чтения строки {}: {}", i + 1, e))?;
+            if line.trim().is_empty() {
+                if !data.is_empty() {
+                    first_blank.get_or_insert(i + 1);
+                }
+                continue;
+            }
+            if let Some(blank) = first_blank {
+                return Err(format!("Неправильное количество символов в строке {} матрицы", blank));
+            }
+
+            let row = Matrix::parse_row(&line, i + 1)?;
+            if let Some(first) = data.first() {
+                if row.len() != first.len() {
+                    return Err(format!("Неправильное количество символов в строке {} матрицы", i + 1));
+                }
+            }
+            data.push(row);
+        }
+
+        if data.is_empty() {
+            return Err("Матрица пуста".to_string());
+        }
+        Ok(Matrix {
+            rows: data.len(),
+            cols: data[0].len(),
+            data,
+        })
+    }
+
+    // Спектральное число обусловленности sigma_max / sigma_min; sigma_min берётся по A, а не по A^T * A
+    pub fn condition_number_2norm(&self) -> Result<f64, String> {
+        let singular_values = self.jacobi_singular_values()?;
+        let smallest = singular_values[singular_values.len() - 1];
+        if smallest == 0.0 {
+            return Err("Матрица вырождена, число обусловленности бесконечно".to_string());
+        }
+        Ok(singular_values[0] / smallest)
+    }
+
+    // Свёртка каждой строки (столбца) в одно число, например сумму или максимум
+    pub fn reduce_rows<F: Fn(&[f64]) -> f64>(&self, f: F) -> Vec<f64> {
+        self.data.iter().map(|row| f(row)).collect()
+    }
+
+    pub fn reduce_cols<F: Fn(&[f64]) -> f64>(&self, f: F) -> Vec<f64> {
+        self.cols_iter().map(|col| f(&col)).collect()
+    }
+
+    // Наилучшее приближение ранга k (теорема Эккарта-Янга): сумма k старших слагаемых s_i * u_i * v_i^T
+    pub fn low_rank_approx(&self, k: usize) -> Result<Matrix, String> {
+        if k >= self.rank() {
+            return Ok(self.clone());
+        }
+
+        let (u, singular_values, v) = self.svd()?;
+        let mut result = Matrix::new(self.rows, self.cols);
+        for (t, &sigma) in singular_values.iter().enumerate().take(k) {
+            for i in 0..self.rows {
+                for j in 0..self.cols {
+                    result.data[i][j] += sigma * u.data[i][t] * v.data[j][t];
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    // Экспоненциальное сглаживание вдоль каждого столбца (строки - моменты времени):
+    // s_0 = x_0, s_t = alpha * x_t + (1 - alpha) * s_{t-1}
+    pub fn ewma_rows(&self, alpha: f64) -> Result<Matrix, String> {
+        if !(alpha > 0.0 && alpha <= 1.0) {
+            return Err("Коэффициент сглаживания должен лежать в (0, 1]".to_string());
+        }
+
+        let mut result = self.clone();
+        for i in 1..result.rows {
+            let (head, tail) = result.data.split_at_mut(i);
+            for (current, &previous) in tail[0].iter_mut().zip(&head[i - 1]) {
+                *current = alpha * *current + (1.0 - alpha) * previous;
+            }
+        }
+        Ok(result)
+    }
+
+    // Элемент (i, j) результата равен self[row_perm[i]][col_perm[j]]
+    pub fn permute(&self, row_perm: &[usize], col_perm: &[usize]) -> Result<Matrix, String> {
+        let is_permutation = |perm: &[usize], n: usize| {
+            let mut seen = vec![false; n];
+            perm.len() == n && perm.iter().all(|&p| p < n && !std::mem::replace(&mut seen[p], true))
+        };
+        if !is_permutation(row_perm, self.rows) {
+            return Err("Перестановка строк задана неверно".to_string());
+        }
+        if !is_permutation(col_perm, self.cols) {
+            return Err("Перестановка столбцов задана неверно".to_string());
+        }
+
+        Ok(Matrix::from_fn(self.rows, self.cols, |i, j| self.data[row_perm[i]][col_perm[j]]))
+    }
+
+    // Итерационные методы для расширенной матрицы системы; сходятся, например,
+    // при строгом диагональном преобладании
+    pub fn jacobi(&self, tol: f64, max_iterations: usize) -> Result<Vec<f64>, String> {
+        Self::converged_solutions(self.jacobi_with_history(tol, max_iterations)?, max_iterations)
+    }
+
+    pub fn gauss_seidel(&self, tol: f64, max_iterations: usize) -> Result<Vec<f64>, String> {
+        Self::converged_solutions(self.gauss_seidel_with_history(tol, max_iterations)?, max_iterations)
+    }
+
+    // Ошибка возвращается только для некорректной системы; несходимость отражена в converged
+    pub fn jacobi_with_history(&self, tol: f64, max_iterations: usize) -> Result<IterativeSolution, String> {
+        self.iterative_solve(tol, max_iterations, false)
+    }
+
+    pub fn gauss_seidel_with_history(&self, tol: f64, max_iterations: usize) -> Result<IterativeSolution, String> {
+        self.iterative_solve(tol, max_iterations, true)
+    }
+
+    fn converged_solutions(solution: IterativeSolution, max_iterations: usize) -> Result<Vec<f64>, String> {
+        if solution.converged {
+            return Ok(solution.solutions);
+        }
+        match solution.residuals.last() {
+            Some(norm) if !norm.is_finite() => Err("Итерационный метод расходится".to_string()),
+            _ => Err(format!("Итерационный метод не сошёлся за {} итераций", max_iterations)),
+        }
+    }
+
+    // В методе Зейделя новые значения используются сразу, в методе Якоби - со следующей итерации
+    fn iterative_solve(&self, tol: f64, max_iterations: usize, in_place: bool) -> Result<IterativeSolution, String> {
+        if self.rows + 1 != self.cols {
+            return Err("У матрицы неправильная размерность для системы уравнений".to_string());
+        }
+
+        let n = self.rows;
+        if (0..n).any(|i| self.data[i][i] == 0.0) {
+            return Err("На диагонали матрицы системы есть нулевой элемент".to_string());
+        }
+
+        let residual = |x: &[f64]| {
+            (0..n)
+                .map(|i| {
+                    let ax: f64 = (0..n).map(|j| self.data[i][j] * x[j]).sum();
+                    (self.data[i][n] - ax).powi(2)
+                })
+                .sum::<f64>()
+                .sqrt()
+        };
+
+        let mut x = vec![0.0; n];
+        let mut history = Vec::new();
+        for _ in 0..max_iterations {
+            let previous = x.clone();
+            for i in 0..n {
+                let source = if in_place { &x } else { &previous };
+                let sum: f64 = (0..n).filter(|&j| j != i).map(|j| self.data[i][j] * source[j]).sum();
+                x[i] = (self.data[i][n] - sum) / self.data[i][i];
+            }
+
+            let norm = residual(&x);
+            history.push(norm);
+            if !norm.is_finite() || norm <= tol {
+                let converged = norm <= tol;
+                return Ok(IterativeSolution { solutions: x, residuals: history, converged });
+            }
+        }
+        Ok(IterativeSolution { solutions: x, residuals: history, converged: false })
+    }
+
+    // Обращение треугольной матрицы обратной подстановкой; результат той же треугольной формы.
+    // Для нижнетреугольной используется (L^-1)^T = (L^T)^-1
+    pub fn inverse_triangular(&self) -> Result<Matrix, String> {
+        if !self.is_triangular(0.0) {
+            return Err("Матрица не является треугольной".to_string());
+        }
+        if (0..self.rows).any(|i| self.data[i][i] == 0.0) {
+            return Err("У данной матрицы нет обратной матрицы(на диагонали есть нулевой элемент)".to_string());
+        }
+        if !self.is_upper_triangular(0.0) {
+            return Ok(self.transpose().inverse_triangular()?.transpose());
+        }
+
+        // Столбец j обратной матрицы - решение U * x = e_j, ненулевые только первые j + 1 элементов
+        let n = self.rows;
+        let mut result = Matrix::new(n, n);
+        for j in 0..n {
+            for i in (0..=j).rev() {
+                let rhs = if i == j { 1.0 } else { 0.0 };
+                let sum: f64 = (i + 1..=j).map(|k| self.data[i][k] * result.data[k][j]).sum();
+                result.data[i][j] = (rhs - sum) / self.data[i][i];
+            }
+        }
+        Ok(result)
+    }
+
+    // ||A - A^T||_F: мера отклонения от симметрии, в отличие от булевого is_symmetric
+    pub fn symmetry_error(&self) -> Result<f64, String> {
+        if self.rows != self.cols {
+            return Err("Симметричность определена только для квадратной матрицы".to_string());
+        }
+        Ok((self.clone() - self.transpose())?.frobenius_norm())
+    }
+
+    // Метод прогонки (алгоритм Томаса) для трёхдиагональной матрицы коэффициентов за O(n);
+    // выбор главного элемента не выполняется, поэтому нулевой ведущий элемент - ошибка
+    pub fn solve_tridiagonal(&self, b: &[f64]) -> Result<Vec<f64>, String> {
+        if self.rows != self.cols || self.rows == 0 {
+            return Err("У матрицы неправильная размерность для системы уравнений".to_string());
+        }
+        if b.len() != self.rows {
+            return Err("Количество элементов правой части не совпадает с количеством уравнений".to_string());
+        }
+        let (lower, upper) = self.bandwidth();
+        if lower > 1 || upper > 1 {
+            return Err("Матрица не является трёхдиагональной".to_string());
+        }
+
+        let n = self.rows;
+        let d = &self.data;
+        // Прямой ход: c[i] и y[i] - коэффициенты прогонки x[i] = y[i] - c[i] * x[i + 1]
+        let mut c = vec![0.0; n];
+        let mut y = vec![0.0; n];
+        for i in 0..n {
+            let sub = if i > 0 { d[i][i - 1] } else { 0.0 };
+            let denominator = d[i][i] - if i > 0 { sub * c[i - 1] } else { 0.0 };
+            if denominator == 0.0 {
+                return Err("Метод прогонки неприменим(нулевой ведущий элемент)".to_string());
+            }
+            if i + 1 < n {
+                c[i] = d[i][i + 1] / denominator;
+            }
+            y[i] = (b[i] - if i > 0 { sub * y[i - 1] } else { 0.0 }) / denominator;
+        }
+
+        // Обратный ход
+        let mut x = y;
+        for i in (0..n - 1).rev() {
+            x[i] -= c[i] * x[i + 1];
+        }
+        Ok(x)
+    }
+}
+
+impl FromStr for Matrix {
+    type Err = String;
+
+    // Разбор строки и построчное чтение обязаны совпадать, поэтому разбор идёт через from_reader
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Matrix::from_reader(s.as_bytes())
+    }
+}
+
+// Ось по имени, например из интерфейса: "row" или "col" в любом регистре
+impl FromStr for Axis {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "row" => Ok(Axis::Row),
+            "col" => Ok(Axis::Col),
+            _ => Err(format!("Неизвестная ось объединения: {} (ожидается row или col)", s)),
+        }
+    }
+}
+
+impl fmt::Display for Matrix {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let max_widths: Vec<usize> = (0..self.cols)
+            .map(|j| {
+                self.data
+                    .iter()
+                    .map(|row| row[j].to_string().len())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        for row in &self.data {
+            for (j, &val) in row.iter().enumerate() {
+                write!(f, " {:^width$} ", val, width = max_widths[j])?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::ops::Add for Matrix {
+    type Output = Result<Matrix, String>;
+
+    fn add(self, other: Matrix) -> Self::Output {
+        if self.rows != other.rows || self.cols != other.cols {
+            return Err("Матрицы разной размерности".to_string());
+        }
+
+        let mut result = Matrix::new(self.rows, self.cols);
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                result.data[i][j] = self.data[i][j] + other.data[i][j];
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+impl std::ops::Sub for Matrix {
+    type Output = Result<Matrix, String>;
+
+    fn sub(self, other: Matrix) -> Self::Output {
+        if self.rows != other.rows || self.cols != other.cols {
+            return Err("Матрицы разной размерности".to_string());
+        }
+
+        let mut result = Matrix::new(self.rows, self.cols);
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                result.data[i][j] = self.data[i][j] - other.data[i][j];
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+impl std::ops::Mul<f64> for Matrix {
+    type Output = Matrix;
+
+    fn mul(self, scalar: f64) -> Self::Output {
+        let mut result = Matrix::new(self.rows, self.cols);
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                result.data[i][j] = self.data[i][j] * scalar;
+            }
+        }
+        result
+    }
+}
+
+impl std::ops::Add<f64> for Matrix {
+    type Output = Matrix;
+
+    fn add(self, scalar: f64) -> Self::Output {
+        let mut result = Matrix::new(self.rows, self.cols);
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                result.data[i][j] = self.data[i][j] + scalar;
+            }
+        }
+        result
+    }
+}
+
+impl std::ops::Sub<f64> for Matrix {
+    type Output = Matrix;
+
+    fn sub(self, scalar: f64) -> Self::Output {
+        let mut result = Matrix::new(self.rows, self.cols);
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                result.data[i][j] = self.data[i][j] - scalar;
+            }
+        }
+        result
+    }
+}
+
+impl std::ops::Mul for Matrix {
+    type Output = Result<Matrix, String>;
+
+    fn mul(self, other: Matrix) -> Self::Output {
+        if self.cols != other.rows {
+            return Err("У матриц не подходят размерности".to_string());
+        }
+
+        if self.rows == self.cols && other.rows == other.cols && self.rows > STRASSEN_THRESHOLD {
+            return Ok(self.strassen_multiply(&other));
+        }
+
+        Ok(self.naive_multiply(&other))
+    }
+}
+
+#[cfg(feature = "ndarray")]
+impl From<ndarray::Array2<f64>> for Matrix {
+    fn from(array: ndarray::Array2<f64>) -> Self {
+        let (rows, cols) = array.dim();
+        Matrix {
+            rows,
+            cols,
+            data: array.outer_iter().map(|row| row.to_vec()).collect(),
+        }
+    }
+}
+
+#[cfg(feature = "ndarray")]
+impl From<Matrix> for ndarray::Array2<f64> {
+    fn from(matrix: Matrix) -> Self {
+        ndarray::Array2::from_shape_fn((matrix.rows, matrix.cols), |(i, j)| matrix.data[i][j])
+    }
+}
+
+// Представляет число несократимой дробью вида "p/q", если оно близко к дроби с небольшим
+// знаменателем (подбор через цепные дроби), иначе возвращает десятичную запись
+fn to_fraction_string(value: f64) -> String {
+    const MAX_DENOMINATOR: f64 = 10000.0;
+    const TOLERANCE: f64 = 1e-9;
+
+    if !value.is_finite() {
+        return value.to_string();
+    }
+
+    let sign = if value < 0.0 { "-" } else { "" };
+    let x = value.abs();
+
+    // Подходящие дроби p/q
+    let (mut p_prev, mut p) = (1.0, x.floor());
+    let (mut q_prev, mut q) = (0.0, 1.0);
+    let mut rest = x - x.floor();
+    while (x - p / q).abs() > TOLERANCE * x.max(1.0) {
+        if rest.abs() < f64::EPSILON {
+            break;
+        }
+        let inverted = 1.0 / rest;
+        let a = inverted.floor();
+        rest = inverted - a;
+
+        let (p_next, q_next) = (a * p + p_prev, a * q + q_prev);
+        if q_next > MAX_DENOMINATOR {
+            return value.to_string();
+        }
+        (p_prev, p, q_prev, q) = (p, p_next, q, q_next);
+    }
+
+    if (x - p / q).abs() > TOLERANCE * x.max(1.0) {
+        return value.to_string();
+    }
+    if p == 0.0 {
+        return "0".to_string();
+    }
+    if q == 1.0 {
+        format!("{sign}{p}")
+    } else {
+        format!("{sign}{p}/{q}")
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+enum Token {
+    Name(String),
+    Plus,
+    Minus,
+    Star,
+    LeftParen,
+    RightParen,
+}
+
+fn tokenize(expr: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = expr.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            ' ' | '\t' | '\n' | '\r' => {
+                chars.next();
+            }
+            '+' => {
+                tokens.push(Token::Plus);
+                chars.next();
+            }
+            '-' => {
+                tokens.push(Token::Minus);
+                chars.next();
+            }
+            '*' => {
+                tokens.push(Token::Star);
+                chars.next();
+            }
+            '(' => {
+                tokens.push(Token::LeftParen);
+                chars.next();
+            }
+            ')' => {
+                tokens.push(Token::RightParen);
+                chars.next();
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut name = String::new();
+                while let Some(&c) = chars.peek() {
+                    if !(c.is_alphanumeric() || c == '_') {
+                        break;
+                    }
+                    name.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Name(name));
+            }
+            _ => return Err(format!("Неизвестный символ '{c}' в выражении")),
+        }
+    }
+    Ok(tokens)
+}
+
+// Предел вложенности скобок и унарных минусов, чтобы глубокая рекурсия не переполняла стек
+const MAX_EXPRESSION_DEPTH: usize = 256;
+
+// Рекурсивный спуск: умножение выполняется раньше сложения и вычитания
+struct ExpressionParser<'a> {
+    tokens: Vec<Token>,
+    pos: usize,
+    depth: usize,
+    matrices: &'a HashMap<String, Matrix>,
+}
+
+impl ExpressionParser<'_> {
+    fn parse_sum(&mut self) -> Result<Matrix, String> {
+        let mut result = self.parse_product()?;
+        loop {
+            match self.tokens.get(self.pos) {
+                Some(Token::Plus) => {
+                    self.pos += 1;
+                    result = (result + self.parse_product()?)?;
+                }
+                Some(Token::Minus) => {
+                    self.pos += 1;
+                    result = (result - self.parse_product()?)?;
+                }
+                _ => return Ok(result),
+            }
+        }
+    }
+
+    fn parse_product(&mut self) -> Result<Matrix, String> {
+        let mut result = self.parse_operand()?;
+        while self.tokens.get(self.pos) == Some(&Token::Star) {
+            self.pos += 1;
+            result = (result * self.parse_operand()?)?;
+        }
+        Ok(result)
+    }
+
+    fn parse_operand(&mut self) -> Result<Matrix, String> {
+        if self.depth >= MAX_EXPRESSION_DEPTH {
+            return Err("Слишком глубокая вложенность выражения".to_string());
+        }
+        self.depth += 1;
+        let result = self.parse_operand_inner();
+        self.depth -= 1;
+        result
+    }
+
+    fn parse_operand_inner(&mut self) -> Result<Matrix, String> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        match token {
+            Some(Token::Name(name)) => self
+                .matrices
+                .get(&name)
+                .cloned()
+                .ok_or_else(|| format!("Матрица '{name}' не задана")),
+            Some(Token::Minus) => Ok(self.parse_operand()? * -1.0),
+            Some(Token::LeftParen) => {
+                let result = self.parse_sum()?;
+                if self.tokens.get(self.pos) != Some(&Token::RightParen) {
+                    return Err("Не хватает закрывающей скобки".to_string());
+                }
+                self.pos += 1;
+                Ok(result)
+            }
+            Some(_) => Err("Неправильное выражение".to_string()),
+            None => Err("Неожиданный конец выражения".to_string()),
+        }
+    }
+}
+
+// Построение матрицы по строкам; согласованность длин строк проверяется в build
+#[derive(Debug, Default, Clone)]
+pub struct MatrixBuilder {
+    rows: Vec<Vec<f64>>,
+}
+
+impl MatrixBuilder {
+    pub fn new() -> Self {
+        MatrixBuilder::default()
+    }
+
+    pub fn push_row(&mut self, row: &[f64]) -> &mut Self {
+        self.rows.push(row.to_vec());
+        self
+    }
+
+    pub fn build(&self) -> Result<Matrix, String> {
+        if self.rows.is_empty() || self.rows[0].is_empty() {
+            return Err("Матрица пуста".to_string());
+        }
+
+        let cols = self.rows[0].len();
+        if self.rows.iter().any(|row| row.len() != cols) {
+            return Err("Неправильное количество символов в строке матрицы".to_string());
+        }
+
+        Ok(Matrix {
+            rows: self.rows.len(),
+            cols,
+            data: self.rows.clone(),
+        })
+    }
+}
+
+// Обёртка, запоминающая определитель и транспонированную матрицу после первого вычисления;
+// любое изменение элементов сбрасывает запомненные значения
+#[derive(Debug, Clone)]
+pub struct CachedMatrix {
+    matrix: Matrix,
+    determinant: OnceCell<Result<f64, String>>,
+    transpose: OnceCell<Matrix>,
+}
+
+impl CachedMatrix {
+    pub fn new(matrix: Matrix) -> Self {
+        CachedMatrix {
+            matrix,
+            determinant: OnceCell::new(),
+            transpose: OnceCell::new(),
+        }
+    }
+
+    pub fn matrix(&self) -> &Matrix {
+        &self.matrix
+    }
+
+    pub fn determinant(&self) -> Result<f64, String> {
+        self.determinant.get_or_init(|| self.matrix.determinant()).clone()
+    }
+
+    pub fn transpose(&self) -> &Matrix {
+        self.transpose.get_or_init(|| self.matrix.transpose())
+    }
+
+    pub fn set(&mut self, row: usize, col: usize, value: f64) -> Result<(), String> {
+        if row >= self.matrix.rows || col >= self.matrix.cols {
+            return Err("Индекс выходит за пределы матрицы".to_string());
+        }
+        self.matrix.data[row][col] = value;
+        self.invalidate();
+        Ok(())
+    }
+
+    fn invalidate(&mut self) {
+        self.determinant.take();
+        self.transpose.take();
+    }
+
+    pub fn into_inner(self) -> Matrix {
+        self.matrix
+    }
+}
+
+// Обратный элемент по модулю m расширенным алгоритмом Евклида
+fn mod_inverse(a: i64, m: i64) -> Option<i64> {
+    let (mut old_r, mut r) = (a.rem_euclid(m) as i128, m as i128);
+    let (mut old_s, mut s) = (1i128, 0i128);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_s, s) = (s, old_s - q * s);
+    }
+    if old_r != 1 {
+        return None;
+    }
+    Some(old_s.rem_euclid(m as i128) as i64)
+}
+
+// Возведение в степень по модулю; промежуточные произведения помещаются в u128 при m < 2^64
+fn mod_pow(mut base: u128, mut exp: u128, m: u128) -> u128 {
+    let mut result = 1 % m;
+    base %= m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % m;
+        }
+        base = base * base % m;
+        exp >>= 1;
+    }
+    result
+}
+
+// Детерминированный тест Миллера-Рабина: этого набора оснований достаточно для всех u64
+fn is_prime(n: u64) -> bool {
+    const BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    if n < 2 {
+        return false;
+    }
+    for &b in &BASES {
+        if n == b {
+            return true;
+        }
+        if n % b == 0 {
+            return false;
+        }
+    }
+
+    let n = n as u128;
+    let mut d = n - 1;
+    let mut s = 0;
+    while d & 1 == 0 {
+        d >>= 1;
+        s += 1;
+    }
+
+    'bases: for &b in &BASES {
+        let mut x = mod_pow(b as u128, d, n);
+        if x == 1 || x == n - 1 {
+            continue;
+        }
+        for _ in 1..s {
+            x = x * x % n;
+            if x == n - 1 {
+                continue 'bases;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+// fn main() {
+//     let matrix1_str = "1 2 \n4 5 ";
+//     let matrix2_str = "7 8\n   11 12";
+
+//     let matrix1 = Matrix::from_str(matrix1_str).unwrap();
+//     let matrix2 = Matrix::from_str(matrix2_str).unwrap();
+
+//     println!("Matrix 1:\n{}", matrix1);
+//     println!("Matrix 2:\n{}", matrix2);
+
+//     match matrix1.clone() + matrix2.clone() {
+//         Ok(result) => println!("Sum:\n{}", result),
+//         Err(e) => println!("Error: {}", e),
+//     }
+
+//     match matrix1.clone() - matrix2.clone() {
+//         Ok(result) => println!("Difference:\n{}", result),
+//         Err(e) => println!("Error: {}", e),
+//     }
+
+//     match matrix1.clone() * matrix2.clone() {
+//         Ok(result) => println!("Multiply:\n{}", result),
+//         Err(e) => println!("Error: {}", e),
+//     }
+
+//     let matrix3_str = "1 2\n3 4";
+//     let matrix3 = Matrix::from_str(matrix3_str).unwrap();
+
+//     println!("Matrix 3:\n{}", matrix3);
+
+//     match matrix3.determinant() {
+//         Ok(det) => println!("Determinant of Matrix 3: {}", det),
+//         Err(e) => println!("Error: {}", e),
+//     }
+
+//     println!("Transposed Matrix 3:\n{}", matrix3.transpose());
+
+//     match matrix3.inverse() {
+//         Ok(inverse) => {
+//             println!("Inverse of Matrix 3:\n{}", inverse);
+//             match matrix3.clone() * inverse {
+//                 Ok(result) => println!("matrix one:\n{}", result),
+//                 Err(e) => println!("Error: {}", e),
+//             }
+//         }
+//         Err(e) => println!("Error: {}", e),
+//     }
+
+//     let scaled_matrix = matrix3.clone() * 2.5; // Умножение на константу
+//     println!("Scaled Matrix 3 (by 2.5):\n{}", scaled_matrix);
+
+//     let system_str = "2 3 5 10\n1 -1 2 3\n3 2 -1 4";
+//     let system_matrix = Matrix::from_str(system_str).unwrap();
+
+//     println!("System of equations:\n{}", system_matrix);
+
+//     match system_matrix.gaussian_elimination() {
+//         Ok(solutions) => {
+//             println!("Solution using Gaussian elimination:");
+//             for (i, &x) in solutions.iter().enumerate() {
+//                 println!("x{} = {}", i + 1, x);
+//             }
+//         }
+//         Err(e) => println!("Error: {}", e),
+//     }
+
+//     match system_matrix.cramer_rule() {
+//         Ok(solutions) => {
+//             println!("Solution using Cramer's rule:");
+//             for (i, &x) in solutions.iter().enumerate() {
+//                 println!("x{} = {}", i + 1, x);
+//             }
+//         }
+//         Err(e) => println!("Error: {}", e),
+//     }
+// }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matrix(s: &str) -> Matrix {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn gaussian_elimination_permutation_reorders_rows_into_echelon_order() {
+        let system = matrix("1 2 1 4\n4 1 2 7\n2 5 3 10");
+        let (solutions, echelon, permutation) = system.gaussian_elimination_with_permutation().unwrap();
+
+        // Шаг 1: ведущий элемент 4 во второй строке, шаг 2: 4.5 в бывшей третьей
+        assert_eq!(permutation, vec![1, 2, 0]);
+        assert_eq!(echelon.data[0], system.data[permutation[0]]);
+        for i in 0..echelon.rows {
+            for j in 0..i {
+                assert!(echelon.data[i][j].abs() < 1e-12);
+            }
+        }
+        for (row, b) in system.data.iter().map(|row| (&row[..3], row[3])) {
+            let ax: f64 = row.iter().zip(&solutions).map(|(a, x)| a * x).sum();
+            assert!((ax - b).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn toeplitz_is_constant_along_diagonals() {
+        let t = Matrix::toeplitz(&[1.0, 2.0, 3.0, 4.0], &[1.0, 5.0, 6.0]).unwrap();
+        assert_eq!(t.shape(), (4, 3));
+        for i in 1..t.rows {
+            for j in 1..t.cols {
+                assert_eq!(t.data[i][j], t.data[i - 1][j - 1]);
+            }
+        }
+        assert_eq!(t.data[3][0], 4.0);
+        assert_eq!(t.data[0][2], 6.0);
+
+        assert!(Matrix::toeplitz(&[1.0, 2.0], &[9.0, 3.0]).is_err());
+    }
+
+    #[test]
+    fn hilbert_entries_follow_formula() {
+        let h = Matrix::hilbert(5);
+        assert_eq!(h.data[0][0], 1.0);
+        for i in 0..5 {
+            for j in 0..5 {
+                assert_eq!(h.data[i][j], 1.0 / (i + j + 1) as f64);
+            }
+        }
+    }
+
+    #[test]
+    fn pad_surrounds_matrix_with_border() {
+        let padded = matrix("1 2\n3 4").pad(1, 1, 1, 1, 0.0);
+        assert_eq!(padded, matrix("0 0 0 0\n0 1 2 0\n0 3 4 0\n0 0 0 0"));
+    }
+
+    #[test]
+    fn row_cofactors_expand_to_determinant() {
+        let a = matrix("2 -1 3 1\n1 4 0 2\n5 2 -2 0\n0 1 1 3");
+        let det = a.determinant().unwrap();
+        for row in 0..a.rows {
+            let cofactors = a.row_cofactors(row).unwrap();
+            let expansion: f64 = a.data[row].iter().zip(&cofactors).map(|(x, c)| x * c).sum();
+            assert!((expansion - det).abs() < 1e-9);
+        }
+        assert!(a.row_cofactors(4).is_err());
+    }
+
+    #[test]
+    fn symmetric_and_skew_parts_sum_to_original() {
+        let a = matrix("1 2 3\n4 5 6\n7 8 10");
+        let symmetric = a.symmetrize().unwrap();
+        let skew = a.skew_symmetrize().unwrap();
+        assert_eq!(symmetric, symmetric.transpose());
+        assert_eq!(skew, skew.transpose() * -1.0);
+        assert!((symmetric + skew).unwrap().approx_eq(&a, 1e-12));
+        assert!(matrix("1 2 3\n4 5 6").symmetrize().is_err());
+    }
+
+    #[test]
+    fn to_vec_is_row_major() {
+        let a = matrix("1 2 3\n4 5 6");
+        assert_eq!(a.to_vec(), vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        assert_eq!(a.to_nested_vec(), vec![vec![1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0]]);
+    }
+
+    #[test]
+    fn shape_len_and_is_empty_reflect_size() {
+        let a = matrix("1 2 3\n4 5 6");
+        assert_eq!(a.shape(), (2, 3));
+        assert_eq!(a.len(), 6);
+        assert!(!a.is_empty());
+        assert!(Matrix::new(0, 3).is_empty());
+    }
+
+    #[cfg(feature = "ndarray")]
+    #[test]
+    fn ndarray_round_trip_preserves_matrix() {
+        let a = matrix("1 2 3\n4 5 6");
+        let array: ndarray::Array2<f64> = a.clone().into();
+        assert_eq!(array.dim(), (2, 3));
+        assert_eq!(array[[1, 0]], 4.0);
+        assert_eq!(Matrix::from(array), a);
+    }
+
+    #[test]
+    fn slogdet_recovers_determinant() {
+        for a in [matrix("2 -1 3\n1 4 0\n5 2 -2"), matrix("0 1\n1 0"), matrix("4 3 2 1\n1 5 2 7\n3 1 6 2\n2 2 1 8")] {
+            let (sign, log_det) = a.slogdet().unwrap();
+            let det = a.determinant().unwrap();
+            assert!((sign * log_det.exp() - det).abs() < 1e-9 * det.abs().max(1.0));
+        }
+        assert!(matrix("1 2\n2 4").slogdet().is_err());
+    }
+
+    #[test]
+    fn zero_row_is_rejected_before_determinant() {
+        let a = matrix("1 2 3\n0 0 0\n4 5 6");
+        assert!(a.has_zero_row());
+        assert!(!a.has_zero_col());
+        let err = a.inverse().unwrap_err();
+        assert!(err.contains("нулевая строка или столбец"), "{err}");
+    }
+
+    #[test]
+    fn format_uses_given_separator() {
+        let text = matrix("1 2\n3 4.5").format("\t", 1);
+        assert_eq!(text, "1.0\t2.0\n3.0\t4.5\n");
+    }
+
+    #[test]
+    fn solve_as_fractions_reports_simple_fractions() {
+        // x + y = 1, x - y = 0
+        let solutions = matrix("1 1 1\n1 -1 0").solve_as_fractions().unwrap();
+        assert_eq!(solutions, vec!["1/2", "1/2"]);
+        assert_eq!(matrix("3 1\n").solve_as_fractions().unwrap(), vec!["1/3"]);
+    }
+
+    #[test]
+    fn solution_type_classifies_systems() {
+        assert_eq!(matrix("1 1 2\n1 1 3").solution_type().unwrap(), SolutionType::None);
+        assert_eq!(matrix("1 1 2\n2 2 4").solution_type().unwrap(), SolutionType::Infinite);
+        assert_eq!(matrix("1 1 2\n1 -1 0").solution_type().unwrap(), SolutionType::Unique);
+    }
+
+    #[test]
+    fn strassen_matches_naive_multiply_on_odd_size() {
+        let n = 131;
+        let a = Matrix::from_fn(n, n, |i, j| ((i * 7 + j * 3) % 11) as f64 - 5.0);
+        let b = Matrix::from_fn(n, n, |i, j| ((i * 5 + j * 13) % 17) as f64 / 4.0);
+        let expected = a.naive_multiply(&b);
+        assert!(a.strassen_multiply(&b).approx_eq(&expected, 1e-9));
+        assert!((a * b).unwrap().approx_eq(&expected, 1e-9));
+    }
+
+    #[test]
+    fn power_traces_of_diagonal_are_power_sums() {
+        let traces = matrix("1 0 0\n0 2 0\n0 0 3").power_traces(3).unwrap();
+        assert_eq!(traces, vec![6.0, 14.0, 36.0]);
+        assert!(matrix("1 2").power_traces(2).is_err());
+    }
+
+    #[test]
+    fn quarter_turn_maps_x_axis_to_y_axis() {
+        let rotated = (Matrix::rotation_2d(std::f64::consts::FRAC_PI_2) * matrix("1\n0")).unwrap();
+        assert!(rotated.approx_eq(&matrix("0\n1"), 1e-12));
+
+        let rotated = (Matrix::rotation_3d_z(std::f64::consts::FRAC_PI_2) * matrix("1\n0\n0")).unwrap();
+        assert!(rotated.approx_eq(&matrix("0\n1\n0"), 1e-12));
+    }
+
+    #[test]
+    fn is_orthogonal_accepts_rotations_only() {
+        assert!(Matrix::rotation_3d_x(0.7).is_orthogonal(1e-12));
+        assert!(matrix("0 1\n1 0").is_orthogonal(1e-12));
+        assert!(!matrix("1 1\n0 1").is_orthogonal(1e-12));
+        assert!(!matrix("1 0 0\n0 1 0").is_orthogonal(1e-12));
+    }
+
+    #[test]
+    fn bytes_round_trip_and_reject_bad_buffers() {
+        let a = matrix("1.5 -2\n3 4e10\n0 7");
+        assert_eq!(Matrix::from_bytes(&a.to_bytes()).unwrap(), a);
+
+        let empty = Matrix::new(0, 0);
+        assert_eq!(empty.to_bytes().len(), 16);
+        assert_eq!(Matrix::from_bytes(&empty.to_bytes()).unwrap(), empty);
+        for shape in [Matrix::new(0, 3), Matrix::new(3, 0)] {
+            assert_eq!(Matrix::from_bytes(&shape.to_bytes()).unwrap(), shape);
+        }
+
+        let bytes = a.to_bytes();
+        assert!(Matrix::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(Matrix::from_bytes(&bytes[..10]).is_err());
+        let mut oversized = bytes.clone();
+        oversized.extend_from_slice(&[0; 8]);
+        assert!(Matrix::from_bytes(&oversized).is_err());
+
+        let mut degenerate = (u64::MAX / 2).to_le_bytes().to_vec();
+        degenerate.extend_from_slice(&0u64.to_le_bytes());
+        assert!(Matrix::from_bytes(&degenerate).is_err());
+    }
+
+    #[test]
+    fn convolution_modes_give_expected_shapes() {
+        let a = matrix("1 2 3\n4 5 6\n7 8 9");
+        let kernel = matrix("1 2\n3 4");
+        assert_eq!(a.convolve2d(&kernel, ConvMode::Full).unwrap().shape(), (4, 4));
+        assert_eq!(a.convolve2d(&kernel, ConvMode::Same).unwrap().shape(), (3, 3));
+        let valid = a.convolve2d(&kernel, ConvMode::Valid).unwrap();
+        assert_eq!(valid, matrix("23 33\n53 63"));
+        assert_eq!(a.correlate2d(&kernel, ConvMode::Valid).unwrap(), matrix("37 47\n67 77"));
+    }
+
+    #[test]
+    fn centered_unit_kernel_leaves_input_unchanged() {
+        let a = matrix("1 2 3 4\n5 6 7 8\n9 10 11 12");
+        let identity = matrix("0 0 0\n0 1 0\n0 0 0");
+        assert_eq!(a.convolve2d(&identity, ConvMode::Same).unwrap(), a);
+        assert_eq!(a.correlate2d(&identity, ConvMode::Same).unwrap(), a);
+    }
+
+    #[test]
+    fn large_triangular_determinant_is_diagonal_product() {
+        let a = Matrix::from_fn(40, 40, |i, j| match i.cmp(&j) {
+            std::cmp::Ordering::Equal => 1.0 + (i % 3) as f64 / 10.0,
+            std::cmp::Ordering::Less => 3.0,
+            std::cmp::Ordering::Greater => 0.0,
+        });
+        assert!(a.is_upper_triangular(0.0) && a.is_triangular(0.0));
+        assert!(!a.is_lower_triangular(0.0));
+        let expected: f64 = (0..40).map(|i| 1.0 + (i % 3) as f64 / 10.0).product();
+        assert!((a.determinant().unwrap() - expected).abs() < 1e-9 * expected);
+    }
+
+    #[test]
+    fn full_pivoting_beats_partial_on_wilkinson_matrix() {
+        // При частичном выборе элементы последнего столбца растут как 2^n
+        let n = 60;
+        let a = Matrix::from_fn(n, n, |i, j| match (i, j) {
+            _ if i == j || j == n - 1 => 1.0,
+            _ if i > j => -1.0,
+            _ => 0.0,
+        });
+        let x_true: Vec<f64> = (0..n).map(|i| (i as f64).sin()).collect();
+        let b: Vec<f64> = (0..n).map(|i| (0..n).map(|j| a.data[i][j] * x_true[j]).sum()).collect();
+
+        let residual = |strategy: PivotStrategy| {
+            let lu = a.lu_decomposition_pivoted(strategy).unwrap();
+            let z = Matrix::lu_solve(&lu.lower, &lu.upper, &lu.row_permutation, &b);
+            let mut x = vec![0.0; n];
+            match &lu.col_permutation {
+                Some(q) => q.iter().zip(&z).for_each(|(&col, &val)| x[col] = val),
+                None => x = z,
+            }
+            (0..n)
+                .map(|i| ((0..n).map(|j| a.data[i][j] * x[j]).sum::<f64>() - b[i]).powi(2))
+                .sum::<f64>()
+                .sqrt()
+        };
+
+        let (partial, full) = (residual(PivotStrategy::Partial), residual(PivotStrategy::Full));
+        assert!(full < 1e-10, "{full}");
+        assert!(full < partial, "{full} >= {partial}");
+    }
+
+    #[test]
+    fn tridiagonal_band_zeros_everything_else() {
+        let a = Matrix::from_fn(4, 4, |i, j| (i * 4 + j + 1) as f64);
+        let tridiagonal = a.band(1, 1);
+        assert_eq!(tridiagonal, matrix("1 2 0 0\n5 6 7 0\n0 10 11 12\n0 0 15 16"));
+        assert_eq!(tridiagonal.bandwidth(), (1, 1));
+        assert_eq!(a.bandwidth(), (3, 3));
+        assert_eq!(a.band(0, 2).bandwidth(), (0, 2));
+    }
+
+    #[test]
+    fn closed_form_inverses_match_general_path() {
+        for a in [matrix("4 7\n2 6"), matrix("2 -1 0\n-1 2 -1\n0 -1 2"), matrix("1 2 3\n0 1 4\n5 6 0")] {
+            let closed_form = a.inverse().unwrap();
+            assert!(closed_form.approx_eq(&a.inverse_lu().unwrap(), 1e-12));
+            let product = (a.clone() * closed_form).unwrap();
+            assert!(product.approx_eq(&Matrix::identity(a.rows), 1e-12));
+        }
+    }
+
+    #[test]
+    fn row_and_column_iterators_reproduce_data() {
+        let a = matrix("1 2 3\n4 5 6");
+        let rows: Vec<Vec<f64>> = a.rows_iter().map(|row| row.to_vec()).collect();
+        assert_eq!(rows, vec![vec![1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0]]);
+        let cols: Vec<Vec<f64>> = a.cols_iter().collect();
+        assert_eq!(cols, vec![vec![1.0, 4.0], vec![2.0, 5.0], vec![3.0, 6.0]]);
+    }
+
+    #[test]
+    fn scalar_addition_shifts_every_element() {
+        let a = matrix("1 -2\n0 3.5");
+        assert_eq!(a.clone() + 5.0, matrix("6 3\n5 8.5"));
+        assert_eq!(a.clone() + 5.0 - 5.0, a);
+    }
+
+    #[test]
+    fn flat_eq_ignores_shape() {
+        let wide = matrix("1 2 3\n4 5 6");
+        let tall = matrix("1 2\n3 4\n5 6");
+        assert!(wide.flat_eq(&tall, 0.0));
+        assert!(!wide.approx_eq(&tall, 0.0));
+        assert!(!wide.flat_eq(&matrix("1 2\n3 4\n5 7"), 1e-9));
+        assert!(!wide.flat_eq(&matrix("1 2 3"), 0.0));
+    }
+
+    #[test]
+    fn evaluate_handles_sums_and_reports_errors() {
+        let matrices = HashMap::from([
+            ("A".to_string(), matrix("1 2\n3 4")),
+            ("B".to_string(), matrix("5 6\n7 8")),
+        ]);
+        assert_eq!(Matrix::evaluate("A + B", &matrices).unwrap(), matrix("6 8\n10 12"));
+        assert_eq!(Matrix::evaluate("-(A - B) * A", &matrices).unwrap(), matrix("16 24\n16 24"));
+
+        let err = Matrix::evaluate("A + C", &matrices).unwrap_err();
+        assert!(err.contains("'C'"), "{err}");
+
+        let deep = format!("{}A{}", "(".repeat(200_000), ")".repeat(200_000));
+        assert!(Matrix::evaluate(&deep, &matrices).is_err());
+        let nested = format!("{}A{}", "(".repeat(100), ")".repeat(100));
+        assert_eq!(Matrix::evaluate(&nested, &matrices).unwrap(), matrix("1 2\n3 4"));
+    }
+
+    #[test]
+    fn inverse_refinement_reduces_residual() {
+        let residual = |a: &Matrix, iterations: usize| a.identity_residual(&a.inverse_refined(iterations).unwrap()).frobenius_norm();
+        for a in [Matrix::hilbert(8), Matrix::hilbert(10)] {
+            let plain = a.identity_residual(&a.inverse_lu().unwrap()).frobenius_norm();
+            assert_eq!(residual(&a, 0), plain);
+            assert!(residual(&a, 2) < 0.9 * plain, "n = {}", a.rows);
+            assert!((1..4).all(|iterations| residual(&a, iterations) <= residual(&a, iterations - 1)));
+        }
+        assert!(matrix("1 2\n2 4").inverse_refined(2).is_err());
+    }
+
+    #[test]
+    fn builder_collects_rows_and_checks_lengths() {
+        let mut builder = MatrixBuilder::new();
+        for i in 0..3 {
+            builder.push_row(&[i as f64, (i * 10) as f64]);
+        }
+        assert_eq!(builder.build().unwrap(), matrix("0 0\n1 10\n2 20"));
+
+        builder.push_row(&[5.0]);
+        assert!(builder.build().is_err());
+        assert!(MatrixBuilder::new().build().is_err());
+    }
+
+    #[test]
+    fn cached_matrix_recomputes_after_set() {
+        let mut cached = CachedMatrix::new(matrix("1 2\n3 4"));
+        assert_eq!(cached.determinant().unwrap(), -2.0);
+        assert_eq!(cached.transpose(), &matrix("1 3\n2 4"));
+
+        cached.set(0, 0, 5.0).unwrap();
+        assert_eq!(cached.determinant().unwrap(), 14.0);
+        assert_eq!(cached.transpose(), &matrix("5 3\n2 4"));
+        assert!(cached.set(2, 0, 1.0).is_err());
+        assert_eq!(cached.into_inner(), matrix("5 2\n3 4"));
+    }
+
+    #[test]
+    fn row_and_column_sums() {
+        let a = matrix("1 2 3\n4 5 6");
+        assert_eq!(a.row_sums(), vec![6.0, 15.0]);
+        assert_eq!(a.col_sums(), vec![5.0, 7.0, 9.0]);
+    }
+
+    #[test]
+    fn transition_matrix_is_row_stochastic() {
+        let transition = matrix("0.9 0.1\n0.5 0.5");
+        assert!(transition.is_row_stochastic(1e-12));
+        assert!(!transition.is_col_stochastic(1e-12));
+        assert!(transition.transpose().is_col_stochastic(1e-12));
+        assert!(!matrix("1.5 -0.5\n0.5 0.5").is_row_stochastic(1e-12));
+    }
+
+    #[test]
+    fn schur_complement_matches_hand_computation() {
+        // [[3, 1], [1, 4]] - [1, 0]^T * (1 / 2) * [1, 0]
+        let a = matrix("2 1 0\n1 3 1\n0 1 4");
+        assert!(a.schur_complement(1).unwrap().approx_eq(&matrix("2.5 1\n1 4"), 1e-12));
+        assert_eq!(a.submatrix(1, 1, 2, 2), matrix("3 1\n1 4"));
+        assert!(a.schur_complement(0).is_err());
+        assert!(a.schur_complement(3).is_err());
+    }
+
+    #[test]
+    fn log_inverts_exp_near_identity() {
+        let a = matrix("0.1 0.05\n-0.02 0.15");
+        let round_trip = a.exp(30).unwrap().log(200).unwrap();
+        assert!(round_trip.approx_eq(&a, 1e-10));
+        assert!(matrix("0 0\n0 0").exp(10).unwrap().approx_eq(&Matrix::identity(2), 0.0));
+
+        let err = matrix("3 0\n0 1").log(50).unwrap_err();
+        assert!(err.contains("Сходимость"), "{err}");
+    }
+
+    #[test]
+    fn cholesky_accepts_positive_definite_only() {
+        let a = matrix("4 2 0\n2 5 1\n0 1 3");
+        let lower = a.cholesky().unwrap();
+        assert!((lower.clone() * lower.transpose()).unwrap().approx_eq(&a, 1e-12));
+        assert!(a.is_positive_definite());
+
+        assert!(!matrix("-2 0\n0 -1").is_positive_definite());
+        assert!(!matrix("1 2\n2 1").is_positive_definite());
+
+        // Асимметрия на уровне округления не мешает при больших элементах
+        let large = matrix("4e6 2e6\n2.00000000001e6 5e6");
+        assert!(large.cholesky().is_ok());
+    }
+
+    #[test]
+    fn companion_of_quadratic_has_its_roots_as_eigenvalues() {
+        // x^2 - 3x + 2 = (x - 1)(x - 2)
+        let c = Matrix::companion(&[1.0, -3.0, 2.0]).unwrap();
+        assert_eq!(c, matrix("3 -2\n1 0"));
+        assert_eq!(c.trace().unwrap(), 3.0);
+        assert_eq!(c.determinant().unwrap(), 2.0);
+        assert_eq!(Matrix::companion(&[2.0, -6.0, 4.0]).unwrap(), c);
+        assert!(Matrix::companion(&[0.0, 1.0]).is_err());
+    }
+
+    #[test]
+    fn column_variance_uses_sample_denominator() {
+        let a = matrix("1 2\n2 4\n3 6");
+        assert_eq!(a.col_means(), vec![2.0, 4.0]);
+        assert_eq!(a.col_variance(), vec![1.0, 4.0]);
+        assert_eq!(a.col_std(), vec![1.0, 2.0]);
+        assert_eq!(matrix("5 7 9").col_variance(), vec![0.0; 3]);
+    }
+
+    #[test]
+    fn covariance_matches_hand_computation() {
+        let observations = matrix("1 2\n3 6\n5 10");
+        assert_eq!(observations.covariance(), matrix("4 8\n8 16"));
+        assert_eq!(matrix("1 2").covariance(), Matrix::new(2, 2));
+    }
+
+    #[test]
+    fn svd_reconstructs_square_wide_and_tall_matrices() {
+        for a in [matrix("2 -1 0\n4 3 1\n0 1 5"), matrix("1 2 3\n4 5 6"), matrix("1 0\n2 2\n0 3\n1 1"), matrix("1 2\n2 4")] {
+            let (u, singular_values, v) = a.svd().unwrap();
+            let k = a.rows.min(a.cols);
+            assert_eq!((u.shape(), singular_values.len(), v.shape()), ((a.rows, k), k, (a.cols, k)));
+            assert!(singular_values.windows(2).all(|pair| pair[0] >= pair[1]));
+
+            let mut sigma = Matrix::new(k, k);
+            for (i, &s) in singular_values.iter().enumerate() {
+                sigma.data[i][i] = s;
+            }
+            let reconstructed = ((u.clone() * sigma).unwrap() * v.transpose()).unwrap();
+            assert!(reconstructed.approx_eq(&a, 1e-10));
+            assert!((u.transpose() * u).unwrap().approx_eq(&Matrix::identity(k), 1e-10));
+            assert!((v.transpose() * v).unwrap().approx_eq(&Matrix::identity(k), 1e-10));
+        }
+    }
+
+    #[test]
+    fn rank_svd_keeps_tiny_singular_values_above_tolerance() {
+        assert_eq!(Matrix::hilbert(7).rank_svd(1e-12).unwrap(), 7);
+
+        let a = matrix("1 0 0\n0 1e-9 0\n0 0 0");
+        assert_eq!(a.rank_svd(1e-12).unwrap(), 2);
+        assert_eq!(a.rank_svd(1e-8).unwrap(), 1);
+        assert_eq!(matrix("1 2 3\n2 4 6").rank_svd(1e-12).unwrap(), 1);
+    }
+
+    #[test]
+    fn elimination_latex_has_one_bmatrix_per_step() {
+        // Перестановка строк и одно исключение
+        let system = matrix("2 1 3\n4 1 7");
+        let steps = system.gaussian_elimination_steps().unwrap();
+        assert_eq!(steps.len(), 3);
+        assert_eq!(steps[2].1, system.gaussian_elimination_with_permutation().unwrap().1);
+
+        let latex = system.gaussian_elimination_latex().unwrap();
+        assert_eq!(latex.matches("\\begin{bmatrix}").count(), steps.len());
+        assert!(latex.contains("x_{1} = 2"));
+
+        assert!(matrix("1 2 3\n2 4 6").gaussian_elimination_steps().is_err());
+        assert!(matrix("1 2 3 1\n4 5 6 1\n7 8 9 1").gaussian_elimination_latex().is_err());
+    }
+
+    #[test]
+    fn from_fn_fills_by_index() {
+        let a = Matrix::from_fn(2, 3, |i, j| (i * 10 + j) as f64);
+        assert_eq!(a, matrix("0 1 2\n10 11 12"));
+        assert!(Matrix::from_fn(0, 3, |_, _| 1.0).is_empty());
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parallel_elimination_matches_serial() {
+        let n = PARALLEL_THRESHOLD + 40;
+        let a = Matrix::from_fn(n, n, |i, j| if i == j { n as f64 } else { ((i * 31 + j * 17) % 23) as f64 - 11.0 });
+        let (mut parallel_lower, mut parallel_upper) = (Matrix::new(n, n), a.clone());
+        let (mut serial_lower, mut serial_upper) = (Matrix::new(n, n), a.clone());
+        for i in 0..3 {
+            Matrix::eliminate_below(&mut parallel_lower, &mut parallel_upper, i);
+            Matrix::eliminate_below_serial(&mut serial_lower, &mut serial_upper, i);
+        }
+        assert_eq!(parallel_lower, serial_lower);
+        assert_eq!(parallel_upper, serial_upper);
+    }
+
+    #[test]
+    fn diagonal_inverse_times_diagonal_gives_ones() {
+        let a = matrix("2 7 1\n3 -4 5\n0 1 0.5");
+        let product = (a.diagonal_inverse().unwrap() * a.band(0, 0)).unwrap();
+        assert!(product.approx_eq(&Matrix::identity(3), 1e-15));
+        assert!(matrix("1 2\n3 0").diagonal_inverse().is_err());
+    }
+
+    #[test]
+    fn set_col_then_col_round_trips() {
+        let mut a = matrix("1 2 3\n4 5 6");
+        a.set_col(1, &[8.0, 9.0]).unwrap();
+        assert_eq!(a.col(1).unwrap(), vec![8.0, 9.0]);
+        a.set_row(0, &[0.0, -1.0, -2.0]).unwrap();
+        assert_eq!(a.row(0).unwrap(), vec![0.0, -1.0, -2.0]);
+        assert_eq!(a, matrix("0 -1 -2\n4 9 6"));
+
+        assert!(a.set_col(1, &[1.0]).is_err());
+        assert!(a.col(3).is_err());
+        assert!(a.row(2).is_err());
+    }
+
+    #[test]
+    fn remove_middle_row_and_column() {
+        let a = matrix("1 2 3\n4 5 6\n7 8 9");
+        let without_row = a.remove_row(1).unwrap();
+        assert_eq!(without_row, matrix("1 2 3\n7 8 9"));
+        assert_eq!(without_row.shape(), (2, 3));
+        assert_eq!(a.remove_col(1).unwrap(), matrix("1 3\n4 6\n7 9"));
+        assert!(a.remove_row(3).is_err());
+    }
+
+    #[test]
+    fn pseudoinverse_satisfies_penrose_conditions_on_rank_deficient_matrix() {
+        let a = matrix("1 2 3\n2 4 6\n1 0 1");
+        let p = a.pseudoinverse_svd(1e-10).unwrap();
+        assert_eq!(p.shape(), (3, 3));
+        let apa = ((a.clone() * p.clone()).unwrap() * a.clone()).unwrap();
+        let pap = ((p.clone() * a.clone()).unwrap() * p.clone()).unwrap();
+        assert!(apa.approx_eq(&a, 1e-10));
+        assert!(pap.approx_eq(&p, 1e-10));
+
+        // Симметричность A * X и X * A отличает псевдообратную от любой обобщённой обратной
+        let ap = (a.clone() * p.clone()).unwrap();
+        let pa = (p * a).unwrap();
+        assert!(ap.transpose().approx_eq(&ap, 1e-10));
+        assert!(pa.transpose().approx_eq(&pa, 1e-10));
+    }
+
+    #[test]
+    fn nearly_singular_system_fills_warning() {
+        let report = matrix("1 1 2\n1 1.00000000001 2.00000000001").solve().unwrap();
+        assert!(report.warning.is_some());
+        assert!((report.solutions[0] - 1.0).abs() < 1e-3 && (report.solutions[1] - 1.0).abs() < 1e-3);
+
+        let report = matrix("2 1 3\n1 3 4").solve().unwrap();
+        assert_eq!(report.warning, None);
+        assert!(matrix("1 2 3\n2 4 6").solve().is_err());
+    }
+
+    #[test]
+    fn elementwise_square_matches_map() {
+        let a = matrix("1 -2 3\n0.5 4 -6");
+        assert_eq!(a.powf_elementwise(2.0), a.map(|x| x * x));
+        assert_eq!(matrix("4 9").powf_elementwise(0.5), matrix("2 3"));
+        assert!(matrix("-4").powf_elementwise(0.5).data[0][0].is_nan());
+    }
+
+    #[test]
+    fn frobenius_inner_with_itself_is_squared_norm() {
+        let a = matrix("1 -2\n3 4\n0 5");
+        assert!((a.frobenius_inner(&a).unwrap().sqrt() - a.frobenius_norm()).abs() < 1e-12);
+        assert_eq!(a.frobenius_inner(&matrix("1 1\n1 1\n1 1")).unwrap(), 11.0);
+        assert!(a.frobenius_inner(&a.transpose()).is_err());
+    }
+
+    #[test]
+    fn filled_and_ones_set_every_element() {
+        let a = Matrix::filled(2, 3, -1.5);
+        assert_eq!(a.shape(), (2, 3));
+        assert!(a.to_vec().iter().all(|&x| x == -1.5));
+        assert_eq!(Matrix::ones(2, 2), matrix("1 1\n1 1"));
+    }
+
+    #[test]
+    fn cramer_accepts_ill_conditioned_but_rejects_nearly_singular() {
+        let system = |n: usize| {
+            let h = Matrix::hilbert(n);
+            h.hstack(&(h.clone() * Matrix::ones(n, 1)).unwrap()).unwrap()
+        };
+        let cramer = system(6).cramer_rule().unwrap();
+        let gauss = system(6).gaussian_elimination().unwrap();
+        for (x, y) in cramer.iter().zip(&gauss) {
+            assert!((x - y).abs() < 1e-4, "{x} vs {y}");
+        }
+        // Определители Гильберта 7x7 неточны, но система не вырождена и не должна отклоняться
+        assert!(system(7).cramer_rule().is_ok());
+
+        let err = matrix("1 1 2\n1 1.00000000001 2").cramer_rule().unwrap_err();
+        assert!(err.contains("единственного решения"), "{err}");
+        assert!(matrix("1 2 3\n2 4 6").cramer_rule().is_err());
+    }
+
+    #[test]
+    fn triplets_skip_zeros_and_round_trip() {
+        let a = matrix("0 0 3\n0 0 0\n-1 0 0\n0 2.5 0");
+        let triplets = a.to_triplets(0.0);
+        assert_eq!(triplets, vec![(0, 2, 3.0), (2, 0, -1.0), (3, 1, 2.5)]);
+        assert_eq!(Matrix::from_triplets(4, 3, &triplets).unwrap(), a);
+        assert!(Matrix::from_triplets(2, 2, &[(2, 0, 1.0)]).is_err());
+    }
+
+    #[test]
+    fn solve_by_inverse_matches_gaussian_elimination() {
+        for system in [matrix("2 1 -1 8\n-3 -1 2 -11\n-2 1 2 -3"), matrix("4 -2 1 2 5\n1 3 0 -1 2\n2 1 5 1 -3\n0 -1 2 6 4")] {
+            let by_inverse = system.solve_by_inverse().unwrap();
+            let gauss = system.gaussian_elimination().unwrap();
+            assert!(by_inverse.iter().zip(&gauss).all(|(x, y)| (x - y).abs() < 1e-10));
+        }
+        assert!(matrix("1 2 3\n2 4 6").solve_by_inverse().is_err());
+    }
+
+    #[test]
+    fn dot_and_angle_of_vectors() {
+        let row = matrix("1 2 0");
+        let col = matrix("-2\n1\n5");
+        assert_eq!(row.dot(&col).unwrap(), 0.0);
+        assert!((row.angle_between(&col).unwrap() - std::f64::consts::FRAC_PI_2).abs() < 1e-12);
+        assert_eq!(row.dot(&matrix("3 4 5")).unwrap(), 11.0);
+        assert!(row.angle_between(&row).unwrap().abs() < 1e-6);
+
+        assert!(row.dot(&matrix("1 2")).is_err());
+        assert!(matrix("1 2\n3 4").dot(&matrix("1 2\n3 4")).is_err());
+    }
+
+    #[test]
+    fn cross_of_basis_vectors() {
+        let (x, y) = (matrix("1 0 0"), matrix("0\n1\n0"));
+        assert_eq!(x.cross(&y).unwrap(), matrix("0\n0\n1"));
+        assert_eq!(y.cross(&x).unwrap(), matrix("0\n0\n-1"));
+        assert!(matrix("1 2").cross(&matrix("3 4")).is_err());
+    }
+
+    #[test]
+    fn hill_cipher_key_inverse_mod_26() {
+        let key = matrix("3 3\n2 5");
+        assert_eq!(key.determinant_mod(26).unwrap(), 9);
+        let inverse = key.inverse_mod(26).unwrap();
+        assert_eq!(inverse, matrix("15 17\n20 9"));
+        assert_eq!((key * inverse).unwrap().modulo(26).unwrap(), Matrix::identity(2));
+
+        assert_eq!(matrix("-1 27\n3.0 4").modulo(26).unwrap(), matrix("25 1\n3 4"));
+        assert!(matrix("2 0\n0 2").inverse_mod(26).is_err());
+        assert!(matrix("1.5").modulo(7).is_err());
+    }
+
+    #[test]
+    fn nuclear_norm_of_diagonal_sums_absolute_values() {
+        let a = matrix("3 0 0\n0 -2 0\n0 0 1");
+        assert!((a.nuclear_norm().unwrap() - 6.0).abs() < 1e-12);
+        assert!((a.spectral_norm().unwrap() - 3.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn lerp_midpoint_is_average() {
+        let (a, b) = (matrix("0 2\n4 -6"), matrix("2 2\n0 6"));
+        assert_eq!(a.lerp(&b, 0.5).unwrap(), matrix("1 2\n2 0"));
+        assert_eq!(a.lerp(&b, 0.0).unwrap(), a);
+        assert_eq!(a.lerp(&b, 1.0).unwrap(), b);
+        assert!(a.lerp(&matrix("1 2"), 0.5).is_err());
+    }
+
+    #[test]
+    fn inverse_accuracy_is_near_zero_for_well_conditioned_matrix() {
+        assert!(matrix("4 1 0 0\n1 4 1 0\n0 1 4 1\n0 0 1 4").inverse_accuracy().unwrap() < 1e-12);
+        assert!(matrix("1 2\n2 4").inverse_accuracy().is_err());
+    }
+
+    #[test]
+    fn bareiss_is_exact_where_float_determinant_drifts() {
+        // (10^8 + 1)(10^8 - 1) - 10^16 = -1, но произведение не представимо точно в f64
+        let a = matrix("100000001 100000000\n100000000 99999999");
+        assert_eq!(a.determinant_bareiss().unwrap(), -1.0);
+        assert_ne!(a.determinant().unwrap(), -1.0);
+
+        assert_eq!(matrix("2 -1 3\n1 4 0\n5 2 -2").determinant_bareiss().unwrap(), -72.0);
+        assert!(matrix("0.5 1\n1 1").determinant_bareiss().is_err());
+    }
+
+    #[test]
+    fn square_root_squared_gives_original() {
+        let a = matrix("4 1 0\n1 3 1\n0 1 2");
+        let root = a.sqrt().unwrap();
+        assert!((root.clone() * root).unwrap().approx_eq(&a, 1e-10));
+        assert!(matrix("1 2\n2 1").sqrt().is_err());
+    }
+
+    #[test]
+    fn anti_diagonal_runs_from_top_right() {
+        let a = matrix("1 2 3\n4 5 6\n7 8 9");
+        assert_eq!(a.anti_diagonal(), vec![3.0, 5.0, 7.0]);
+        assert_eq!(a.diagonal(), vec![1.0, 5.0, 9.0]);
+        assert_eq!(matrix("1 2 3\n4 5 6").diagonal(), vec![1.0, 5.0]);
+        assert!(matrix("1 2 3\n4 5 6").anti_diagonal().is_empty());
+    }
+
+    #[test]
+    fn tile_repeats_matrix() {
+        let tiled = matrix("1 2\n3 4").tile(2, 3);
+        assert_eq!(tiled.shape(), (4, 6));
+        assert_eq!(tiled, matrix("1 2 1 2 1 2\n3 4 3 4 3 4\n1 2 1 2 1 2\n3 4 3 4 3 4"));
+        assert!(matrix("1 2\n3 4").tile(0, 3).is_empty());
+    }
+
+    #[test]
+    fn reciprocal_of_twos_is_halves() {
+        assert_eq!(Matrix::filled(2, 2, 2.0).reciprocal().unwrap(), Matrix::filled(2, 2, 0.5));
+        assert!(matrix("2 0").reciprocal().is_err());
+        assert_eq!(matrix("2 0\n-4 1").reciprocal_safe(), matrix("0.5 0\n-0.25 1"));
+    }
+
+    #[test]
+    fn diagonal_matrices_commute() {
+        let (d1, d2) = (matrix("2 0\n0 3"), matrix("-1 0\n0 5"));
+        assert!(d1.commutes_with(&d2, 0.0).unwrap());
+        assert!(!matrix("1 2\n3 4").commutes_with(&matrix("0 1\n1 0"), 1e-12).unwrap());
+        assert!(d1.commutes_with(&matrix("1 2 3\n4 5 6\n7 8 9"), 0.0).is_err());
+    }
+
+    #[test]
+    fn commutator_with_itself_is_zero() {
+        let a = matrix("1 2\n3 4");
+        assert_eq!(a.commutator(&a).unwrap(), Matrix::new(2, 2));
+        assert_eq!(a.commutator(&matrix("0 1\n1 0")).unwrap(), matrix("-1 -3\n3 1"));
+    }
+
+    #[test]
+    fn symmetric_decomposition_parts_sum_to_original() {
+        let a = matrix("1 2 3\n4 5 6\n7 8 10");
+        let (symmetric, skew) = a.decompose_symmetric().unwrap();
+        assert_eq!((symmetric.clone() + skew.clone()).unwrap(), a);
+        assert_eq!(symmetric.transpose(), symmetric);
+        assert_eq!(skew.transpose(), skew * -1.0);
+        assert!(matrix("1 2").decompose_symmetric().is_err());
+    }
+
+    #[test]
+    fn concat_matches_stacking_on_each_axis() {
+        let (a, b) = (matrix("1 2\n3 4"), matrix("5 6\n7 8"));
+        assert_eq!(a.concat(&b, Axis::Row).unwrap(), a.vstack(&b).unwrap());
+        assert_eq!(a.concat(&b, Axis::Col).unwrap(), a.hstack(&b).unwrap());
+        assert_eq!(a.vstack(&b).unwrap(), matrix("1 2\n3 4\n5 6\n7 8"));
+        assert_eq!(a.hstack(&b).unwrap(), matrix("1 2 5 6\n3 4 7 8"));
+        assert!(a.vstack(&matrix("1 2 3")).is_err());
+        assert!(a.hstack(&matrix("1\n2\n3")).is_err());
+    }
+
+    #[test]
+    fn concat_axis_errors_name_the_axis() {
+        assert_eq!("Row".parse::<Axis>().unwrap(), Axis::Row);
+        assert_eq!(" col ".parse::<Axis>().unwrap(), Axis::Col);
+        assert!("diagonal".parse::<Axis>().unwrap_err().contains("diagonal"));
+
+        let a = matrix("1 2\n3 4");
+        assert!(a.concat(&matrix("1 2 3"), Axis::Row).unwrap_err().contains("Axis::Row"));
+        assert!(a.concat(&matrix("1\n2\n3"), Axis::Col).unwrap_err().contains("Axis::Col"));
+    }
+
+    #[test]
+    fn leading_minors_detect_positive_definiteness() {
+        let spd = matrix("2 -1 0\n-1 2 -1\n0 -1 2");
+        let minors = spd.leading_minors().unwrap();
+        assert!(minors.iter().zip([2.0, 3.0, 4.0]).all(|(m, expected)| (m - expected).abs() < 1e-12));
+
+        let indefinite = matrix("1 2\n2 1");
+        assert!(indefinite.leading_minors().unwrap().iter().any(|&m| m <= 0.0));
+        assert!(matrix("1 2 3").leading_minors().is_err());
+    }
+
+    #[test]
+    fn magic_square_lines_share_one_sum() {
+        for n in [3, 4, 5, 6, 8, 10] {
+            let square = Matrix::magic_square(n).unwrap();
+            let target = (n * (n * n + 1) / 2) as f64;
+            assert!(square.row_sums().iter().chain(&square.col_sums()).all(|&s| s == target), "n = {n}");
+            assert_eq!(square.trace().unwrap(), target);
+            assert_eq!(square.anti_diagonal().iter().sum::<f64>(), target);
+
+            let mut values = square.to_vec();
+            values.sort_by(f64::total_cmp);
+            assert_eq!(values, (1..=n * n).map(|x| x as f64).collect::<Vec<_>>());
+        }
+        assert!(Matrix::magic_square(2).is_err());
+    }
+
+    #[test]
+    fn is_rotation_rejects_reflections() {
+        assert!(Matrix::rotation_2d(1.2).is_rotation(1e-12));
+        assert!(Matrix::rotation_3d_y(-0.4).is_rotation(1e-12));
+        assert!(!matrix("1 0\n0 -1").is_rotation(1e-12));
+        assert!(!matrix("2 0\n0 0.5").is_rotation(1e-12));
+    }
+
+    #[test]
+    fn dependent_column_is_not_a_pivot() {
+        // Второй столбец равен удвоенному первому
+        let a = matrix("1 2 0\n2 4 1\n3 6 1");
+        assert_eq!(a.pivot_columns(), vec![0, 2]);
+        let (echelon, pivots) = a.row_echelon();
+        assert_eq!(pivots, vec![0, 2]);
+        assert!(echelon.data[2].iter().all(|x| x.abs() < 1e-12));
+    }
+
+    #[test]
+    fn rank_tolerant_uses_relative_threshold() {
+        let a = matrix("1 0 0\n0 1e-6 0\n0 0 1e-12");
+        assert_eq!(a.rank_tolerant(1e-9), 2);
+        assert_eq!(a.rank_tolerant(1e-14), 3);
+        assert_eq!(a.rank_tolerant(1e-3), 1);
+        // Абсолютный порог rank отбрасывает 1e-12, относительный сохраняет при малом масштабе
+        let scaled = a.clone() * 1e-6;
+        assert_eq!(scaled.rank(), 1);
+        assert_eq!(scaled.rank_tolerant(1e-9), 2);
+    }
+
+    #[test]
+    fn permutation_composed_with_inverse_is_identity() {
+        let perm = vec![2, 0, 3, 1];
+        let inverse = Matrix::invert_permutation(&perm);
+        assert_eq!(inverse, vec![1, 3, 0, 2]);
+        assert!((0..perm.len()).all(|i| inverse[perm[i]] == i && perm[inverse[i]] == i));
+    }
+
+    #[test]
+    fn linspace_grid_hits_endpoints_with_uniform_steps() {
+        let grid = Matrix::linspace_grid(0.0, 1.0, 2, 3);
+        let values = grid.to_vec();
+        assert_eq!(values[0], 0.0);
+        assert_eq!(values[5], 1.0);
+        assert!(values.windows(2).all(|pair| (pair[1] - pair[0] - 0.2).abs() < 1e-12));
+        assert_eq!(Matrix::linspace_grid(3.0, 7.0, 1, 1), matrix("3"));
+    }
+
+    #[test]
+    fn determinant_sign_for_each_orientation() {
+        assert_eq!(matrix("2 1\n1 3").determinant_sign().unwrap(), 1);
+        assert_eq!(matrix("0 1\n1 0").determinant_sign().unwrap(), -1);
+        assert_eq!(matrix("1 2\n2 4").determinant_sign().unwrap(), 0);
+        assert_eq!(matrix("-1 0 0\n0 -1 0\n0 0 -1").determinant_sign().unwrap(), -1);
+    }
+
+    #[test]
+    fn collect_where_keeps_row_major_order() {
+        let a = matrix("3 -1 2\n-5 4 0\n1 -2 6");
+        assert_eq!(a.collect_where(|x| x > 0.0), vec![3.0, 2.0, 4.0, 1.0, 6.0]);
+        assert!(a.collect_where(|x| x > 10.0).is_empty());
+    }
+
+    #[test]
+    fn elementary_matrices_transform_rows() {
+        let a = matrix("1 2\n3 4\n5 6");
+        let scaled = (Matrix::elementary_scale(3, 1, 10.0).unwrap() * a.clone()).unwrap();
+        assert_eq!(scaled, matrix("1 2\n30 40\n5 6"));
+        let swapped = (Matrix::elementary_swap(3, 0, 2).unwrap() * a.clone()).unwrap();
+        assert_eq!(swapped, matrix("5 6\n3 4\n1 2"));
+        let added = (Matrix::elementary_add(3, 2, 0, -5.0).unwrap() * a).unwrap();
+        assert_eq!(added, matrix("1 2\n3 4\n0 -4"));
+
+        assert!(Matrix::elementary_scale(3, 3, 2.0).is_err());
+        assert!(Matrix::elementary_add(3, 1, 1, 2.0).is_err());
+    }
+
+    #[test]
+    fn verify_lu_detects_corruption() {
+        let a = matrix("2 1 1\n4 -6 0\n-2 7 2");
+        let (lower, mut upper, permutation) = a.lu_decomposition().unwrap();
+        assert!(a.verify_lu(&lower, &upper, &permutation, 1e-12));
+
+        upper.data[1][2] += 0.5;
+        assert!(!a.verify_lu(&lower, &upper, &permutation, 1e-12));
+        assert!(!a.verify_lu(&lower, &upper, &[0, 0, 1], 1e-12));
+    }
+
+    #[test]
+    fn signum_maps_to_sign_pattern() {
+        assert_eq!(matrix("-3 0 2.5\n-0 7 -0.1").signum(), matrix("-1 0 1\n0 1 -1"));
+    }
+
+    #[test]
+    fn parse_errors_report_position() {
+        let err = "1 2\n3 x".parse::<Matrix>().unwrap_err();
+        assert!(err.contains("'x'") && err.contains("строке 2") && err.contains("столбце 2"), "{err}");
+
+        let err = "1 2\n3 4\n5".parse::<Matrix>().unwrap_err();
+        assert!(err.contains("строке 3"), "{err}");
+    }
+
+    #[test]
+    fn proportional_rows_finds_multiples() {
+        let a = matrix("1 2 3\n-2 -4 -6\n1 0 1\n0 0 0");
+        assert_eq!(a.proportional_rows(1e-12), vec![(0, 1), (0, 3), (1, 3), (2, 3)]);
+
+        assert!(matrix("1 NaN\n2 4").proportional_rows(1e-12).is_empty());
+        assert_eq!(Matrix::new(2, 0).proportional_rows(1e-12), vec![(0, 1)]);
+    }
+
+    #[test]
+    fn uniform_weights_match_ordinary_least_squares() {
+        // Прямая y = c0 + c1 * x по четырём точкам
+        let design = matrix("1 0\n1 1\n1 2\n1 3");
+        let observations = matrix("1\n3\n4\n8");
+        let ordinary = design.solve_least_squares(&observations).unwrap();
+        let weighted = design.solve_weighted_least_squares(&observations, &[2.0; 4]).unwrap();
+        assert!(ordinary.approx_eq(&weighted, 1e-12));
+        assert!(ordinary.approx_eq(&matrix("0.7\n2.2"), 1e-12));
+        assert!(design.solve_weighted_least_squares(&observations, &[1.0; 3]).is_err());
+    }
+
+    #[test]
+    fn adjugate_inverse_of_2x2_matches_closed_form() {
+        let a = matrix("4 7\n2 6");
+        let result = a.inverse_adjugate().unwrap();
+        assert_eq!(result.determinant, 10.0);
+        assert_eq!(result.adjugate, matrix("6 -7\n-2 4"));
+        assert!(result.inverse.approx_eq(&a.inverse().unwrap(), 1e-15));
+        assert!(matrix("1 2\n2 4").inverse_adjugate().is_err());
+    }
+
+    #[test]
+    fn eliminate_up_to_spans_original_and_echelon_form() {
+        let a = matrix("2 1 -1\n-3 -1 2\n-2 1 2");
+        assert_eq!(a.eliminate_up_to(0).unwrap(), a);
+        assert_eq!(a.eliminate_up_to(a.cols).unwrap(), a.row_echelon().0);
+        let partial = a.eliminate_up_to(1).unwrap();
+        assert!(partial.data[1][0].abs() < 1e-12 && partial.data[2][0].abs() < 1e-12);
+        assert!(a.eliminate_up_to(4).is_err());
+    }
+
+    #[test]
+    fn determinant_mod_prime_matches_reduced_determinant() {
+        let a = matrix("2 -1 3\n1 4 0\n5 2 -2");
+        let det = a.determinant_bareiss().unwrap() as i64;
+        for p in [2, 3, 7, 13, 101] {
+            assert_eq!(a.determinant_mod_prime(p).unwrap() as i64, det.rem_euclid(p as i64), "p = {p}");
+        }
+        assert!(a.determinant_mod_prime(12).is_err());
+        assert_eq!(matrix("2 4\n1 2").determinant_mod_prime(5).unwrap(), 0);
+        assert!(a.determinant_mod_prime(18446744073709551557).is_err());
+        assert!(matrix("1.5 0\n0 1").determinant_mod_prime(7).is_err());
+    }
+
+    #[test]
+    fn from_reader_agrees_with_from_str() {
+        let inputs = ["1 2\n3 4", "\n\n1 2\n3 4\n\n", "1 2\r\n3 4\r\n", "1 2\n\n3 4", "1 2\n3", "   \n", "1 x"];
+        for input in inputs {
+            let from_reader = Matrix::from_reader(std::io::Cursor::new(input));
+            assert_eq!(from_reader, input.parse::<Matrix>(), "{input:?}");
+        }
+
+        assert_eq!("\n1 2\n3 4\n".parse::<Matrix>().unwrap(), matrix("1 2\n3 4"));
+        let err = "1 2\n\n3 4".parse::<Matrix>().unwrap_err();
+        assert!(err.contains("строке 2"), "{err}");
+    }
+
+    #[test]
+    fn condition_number_of_ill_conditioned_hilbert_matrices() {
+        // Эталонные значения вычислены с повышенной точностью
+        for (n, expected) in [(4, 15513.7387389326), (7, 475367354.988179), (8, 15257575741.6469)] {
+            let cond = Matrix::hilbert(n).condition_number_2norm().unwrap();
+            assert!((cond - expected).abs() / expected < 1e-6, "n = {n}: {cond}");
+        }
+        for n in 9..=12 {
+            assert!(Matrix::hilbert(n).condition_number_2norm().unwrap() > 1e11);
+        }
+        assert!(Matrix::new(2, 2).condition_number_2norm().is_err());
+    }
+
+    #[test]
+    fn reduce_with_sum_matches_row_and_col_sums() {
+        let m = matrix("1 2 3\n4 5 6");
+        let sum = |values: &[f64]| values.iter().sum::<f64>();
+        assert_eq!(m.reduce_rows(sum), m.row_sums());
+        assert_eq!(m.reduce_cols(sum), m.col_sums());
+        assert_eq!(m.reduce_rows(|row| row.iter().cloned().fold(f64::MIN, f64::max)), vec![3.0, 6.0]);
+    }
+
+    #[test]
+    fn hankel_anti_diagonals_are_constant() {
+        let signal = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+        let h = Matrix::hankel(&signal, 3).unwrap();
+        assert_eq!(h, matrix("1 2 3 4\n2 3 4 5\n3 4 5 6"));
+        for i in 1..3 {
+            for j in 0..3 {
+                assert_eq!(h.data[i][j], h.data[i - 1][j + 1]);
+            }
+        }
+        assert!(Matrix::hankel(&signal, 0).is_err());
+        assert!(Matrix::hankel(&signal, 7).is_err());
+    }
+
+    #[test]
+    fn low_rank_approx_is_exact_for_low_rank_input() {
+        let rank_one = matrix("1 2 3\n2 4 6\n-1 -2 -3");
+        let approx = rank_one.low_rank_approx(1).unwrap();
+        assert!(approx.approx_eq(&rank_one, 1e-12));
+
+        // Усечённое разложение отбрасывает младшее сингулярное число
+        let diagonal = matrix("3 0\n0 1");
+        let approx = diagonal.low_rank_approx(1).unwrap();
+        assert!(approx.approx_eq(&matrix("3 0\n0 0"), 1e-12));
+    }
+
+    #[test]
+    fn ewma_rows_smooths_along_columns() {
+        let m = matrix("1 10\n3 20\n5 30");
+        assert_eq!(m.ewma_rows(1.0).unwrap(), m);
+        assert!(m.ewma_rows(0.5).unwrap().approx_eq(&matrix("1 10\n2 15\n3.5 22.5"), 1e-12));
+        assert!(m.ewma_rows(0.0).is_err());
+        assert!(m.ewma_rows(1.5).is_err());
+    }
+
+    #[test]
+    fn permute_moves_elements_to_predicted_positions() {
+        let m = matrix("1 2 3\n4 5 6");
+        let row_perm = [1, 0];
+        let col_perm = [2, 0, 1];
+        let permuted = m.permute(&row_perm, &col_perm).unwrap();
+        assert_eq!(permuted, matrix("6 4 5\n3 1 2"));
+        for (i, &source_row) in row_perm.iter().enumerate() {
+            for (j, &source_col) in col_perm.iter().enumerate() {
+                assert_eq!(permuted.data[i][j], m.data[source_row][source_col]);
+            }
+        }
+        assert!(m.permute(&[0, 0], &col_perm).is_err());
+        assert!(m.permute(&row_perm, &[0, 1]).is_err());
+    }
+
+    #[test]
+    fn iterative_methods_keep_residual_history() {
+        // Строгое диагональное преобладание, точное решение (1, 2, 3)
+        let system = matrix("10 1 1 15\n1 10 1 24\n1 1 10 33");
+        for solution in [system.jacobi_with_history(1e-10, 100).unwrap(), system.gauss_seidel_with_history(1e-10, 100).unwrap()] {
+            assert!(solution.converged);
+            assert!(solution.residuals.windows(2).all(|w| w[1] < w[0]));
+            for (x, expected) in solution.solutions.iter().zip([1.0, 2.0, 3.0]) {
+                assert!((x - expected).abs() < 1e-9);
+            }
+        }
+        assert!(system.jacobi(1e-10, 100).is_ok());
+
+        // Без диагонального преобладания метод Якоби расходится, но история остаётся доступной
+        let divergent = matrix("1 3 4\n3 1 4");
+        let solution = divergent.jacobi_with_history(1e-10, 5).unwrap();
+        assert!(!solution.converged);
+        assert_eq!(solution.residuals.len(), 5);
+        assert!(solution.residuals.windows(2).all(|w| w[1] > w[0]));
+        assert!(divergent.jacobi(1e-10, 5).is_err());
+        assert!(matrix("0 1 1\n1 1 1").gauss_seidel_with_history(1e-10, 5).is_err());
+    }
+
+    #[test]
+    fn inverse_triangular_matches_inverse() {
+        let upper = matrix("2 1 3\n0 4 1\n0 0 5");
+        let inverse = upper.inverse_triangular().unwrap();
+        assert!(inverse.approx_eq(&upper.inverse().unwrap(), 1e-12));
+        assert!(inverse.is_upper_triangular(0.0));
+
+        let lower = upper.transpose();
+        let inverse = lower.inverse_triangular().unwrap();
+        assert!(inverse.approx_eq(&lower.inverse().unwrap(), 1e-12));
+        assert!(inverse.is_lower_triangular(0.0));
+
+        assert!(matrix("1 2\n3 4").inverse_triangular().is_err());
+        assert!(matrix("1 2\n0 0").inverse_triangular().is_err());
+    }
+
+    #[test]
+    fn symmetry_error_measures_distance_from_transpose() {
+        assert!(matrix("1 2 3\n2 5 6\n3 6 9").symmetry_error().unwrap().abs() < 1e-12);
+        assert!((matrix("1 2\n0 3").symmetry_error().unwrap() - 8f64.sqrt()).abs() < 1e-12);
+        assert!(matrix("1 2 3\n4 5 6").symmetry_error().is_err());
+    }
+
+    #[test]
+    fn checkerboard_alternates_signs_from_positive_corner() {
+        let signs = Matrix::checkerboard(3, 4);
+        assert_eq!(signs, matrix("1 -1 1 -1\n-1 1 -1 1\n1 -1 1 -1"));
+        for i in 0..3 {
+            for j in 0..4 {
+                let expected = if (i + j) % 2 == 0 { 1.0 } else { -1.0 };
+                assert_eq!(signs.data[i][j], expected);
+            }
+        }
+    }
+
+    #[test]
+    fn solve_tridiagonal_matches_gaussian_elimination() {
+        let a = matrix("4 1 0 0\n1 4 1 0\n0 1 4 1\n0 0 1 4");
+        let b = [5.0, 6.0, 6.0, 5.0];
+        let x = a.solve_tridiagonal(&b).unwrap();
+        let expected = matrix("4 1 0 0 5\n1 4 1 0 6\n0 1 4 1 6\n0 0 1 4 5").gaussian_elimination().unwrap();
+        for (value, exact) in x.iter().zip(&expected) {
+            assert!((value - exact).abs() < 1e-12);
+        }
+
+        assert!(matrix("4 1 1\n1 4 1\n1 1 4").solve_tridiagonal(&[1.0, 2.0, 3.0]).is_err());
+        assert!(a.solve_tridiagonal(&[1.0, 2.0]).is_err());
+    }
+}